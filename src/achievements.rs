@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use crate::state::PlayerStats;
+
+/// Серия побед для достижения "5 побед подряд"
+const STREAK_LENGTH: u32 = 5;
+
+/// Ставка, начиная с которой игрок получает "Хайроллер"
+const HIGH_ROLLER_BET: u64 = 10_000;
+
+/// Достижение игрока
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Achievement {
+    FirstWin,   // Первая победа
+    FiveStreak, // 5 побед подряд
+    HighRoller, // Крупная ставка
+}
+
+/// Учет достижений одного игрока
+///
+/// `check` вызывается после каждого раунда: серия побед восстанавливается
+/// по разнице между текущей и предыдущей статистикой, поэтому пропуск
+/// вызовов может разорвать серию. Каждое достижение выдается один раз.
+#[derive(Debug, Default)]
+pub struct AchievementTracker {
+    unlocked: HashSet<Achievement>,
+    last: PlayerStats,
+    streak: u32,
+    high_roller: bool,
+}
+
+impl AchievementTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Учет сделанной ставки (для "Хайроллера")
+    pub fn observe_bet(&mut self, amount: u64) {
+        self.high_roller |= amount >= HIGH_ROLLER_BET;
+    }
+
+    /// Новые достижения после обновления статистики
+    pub fn check(&mut self, stats: &PlayerStats) -> Vec<Achievement> {
+        if stats.losses > self.last.losses {
+            self.streak = 0;
+        } else {
+            let new_wins = stats.wins.saturating_sub(self.last.wins);
+            self.streak = self
+                .streak
+                .saturating_add(new_wins.min(u32::MAX as u64) as u32);
+        }
+        self.last = stats.clone();
+
+        let earned = [
+            (Achievement::FirstWin, stats.wins > 0),
+            (Achievement::FiveStreak, self.streak >= STREAK_LENGTH),
+            (Achievement::HighRoller, self.high_roller),
+        ];
+        earned
+            .into_iter()
+            .filter(|&(achievement, earned)| earned && self.unlocked.insert(achievement))
+            .map(|(achievement, _)| achievement)
+            .collect()
+    }
+
+    /// Все полученные достижения
+    pub fn unlocked(&self) -> &HashSet<Achievement> {
+        &self.unlocked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn stats(wins: u64, losses: u64) -> PlayerStats {
+        PlayerStats::from_tuple(wins, losses).unwrap()
+    }
+
+    #[test]
+    fn test_check_first_win_unlocks_once() {
+        let mut tracker = AchievementTracker::new();
+        assert_eq!(tracker.check(&stats(0, 1)), vec![]);
+        assert_eq!(tracker.check(&stats(1, 1)), vec![Achievement::FirstWin]);
+        assert_eq!(tracker.check(&stats(2, 1)), vec![]);
+    }
+
+    #[test]
+    fn test_check_five_streak() {
+        let mut tracker = AchievementTracker::new();
+        for wins in 1..=4 {
+            tracker.check(&stats(wins, 0));
+        }
+        assert_eq!(tracker.check(&stats(5, 0)), vec![Achievement::FiveStreak]);
+        assert_eq!(tracker.check(&stats(6, 0)), vec![]);
+    }
+
+    #[test]
+    fn test_check_loss_breaks_streak() {
+        let mut tracker = AchievementTracker::new();
+        for wins in 1..=4 {
+            tracker.check(&stats(wins, 0));
+        }
+        tracker.check(&stats(4, 1));
+        for wins in 5..=8 {
+            assert!(!tracker
+                .check(&stats(wins, 1))
+                .contains(&Achievement::FiveStreak));
+        }
+    }
+
+    #[test]
+    fn test_check_high_roller() {
+        let mut tracker = AchievementTracker::new();
+        tracker.observe_bet(100);
+        assert_eq!(tracker.check(&stats(0, 1)), vec![]);
+        tracker.observe_bet(HIGH_ROLLER_BET);
+        assert_eq!(tracker.check(&stats(0, 2)), vec![Achievement::HighRoller]);
+        assert!(tracker.unlocked().contains(&Achievement::HighRoller));
+    }
+}
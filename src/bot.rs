@@ -7,8 +7,8 @@ use teloxide::{
     RequestError,
 };
 
-use crate::game::DiceGame;
-use crate::state::{EvenOddChoice, GuessOneChoice, HighLowChoice};
+use telegram_dice_bot::game::DiceGame;
+use telegram_dice_bot::state::{EvenOddChoice, GuessOneChoice, HighLowChoice};
 
 /// Команды бота
 #[derive(BotCommands, Clone)]
@@ -293,7 +293,7 @@ impl BotHandler {
         if let Some(dice) = dice_message.dice() {
             let dice_result = dice.value as u8;
             let is_win = DiceGame::check_even_odd(dice_result, choice.clone());
-            let result_text = if dice_result % 2 == 0 {
+            let result_text = if dice_result.is_multiple_of(2) {
                 "четное"
             } else {
                 "нечетное"
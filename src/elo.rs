@@ -0,0 +1,55 @@
+/// Ожидаемый результат игрока с рейтингом `rating` против `opponent`
+fn expected_score(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+/// Обновление рейтингов Эло после победы одного из игроков
+///
+/// Возвращает новые рейтинги `(победитель, проигравший)`.
+pub fn update_ratings(winner: f64, loser: f64, k: f64) -> (f64, f64) {
+    let delta = k * (1.0 - expected_score(winner, loser));
+    (winner + delta, loser - delta)
+}
+
+/// Обновление рейтингов Эло после ничьей
+///
+/// Рейтинги сближаются: более сильный игрок теряет столько же,
+/// сколько получает более слабый.
+pub fn update_ratings_draw(a: f64, b: f64, k: f64) -> (f64, f64) {
+    let delta = k * (0.5 - expected_score(a, b));
+    (a + delta, b - delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_ratings_underdog_gains_more() {
+        let (underdog_after, _) = update_ratings(1400.0, 1600.0, 32.0);
+        let (favorite_after, _) = update_ratings(1600.0, 1400.0, 32.0);
+        assert!(underdog_after - 1400.0 > favorite_after - 1600.0);
+    }
+
+    #[test]
+    fn test_update_ratings_equal_players() {
+        let (winner, loser) = update_ratings(1500.0, 1500.0, 32.0);
+        assert!((winner - 1516.0).abs() < 1e-9);
+        assert!((loser - 1484.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_update_ratings_draw_moves_toward_mean() {
+        let (strong, weak) = update_ratings_draw(1700.0, 1300.0, 32.0);
+        assert!(strong < 1700.0);
+        assert!(weak > 1300.0);
+        assert!((strong + weak - 3000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_update_ratings_draw_equal_players_unchanged() {
+        let (a, b) = update_ratings_draw(1500.0, 1500.0, 32.0);
+        assert!((a - 1500.0).abs() < 1e-9);
+        assert!((b - 1500.0).abs() < 1e-9);
+    }
+}
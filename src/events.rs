@@ -0,0 +1,83 @@
+use crate::state::GameEvent;
+
+/// Подписчик на игровые события (статистика, логирование, достижения)
+///
+/// Получает событие по общей ссылке и не может его изменить; подписчикам
+/// с собственным состоянием нужна внутренняя изменяемость.
+pub trait EventHandler: Send + Sync {
+    fn handle(&self, event: &GameEvent);
+}
+
+/// Шина событий, рассылающая каждое событие всем подписчикам
+#[derive(Default)]
+pub struct EventBus {
+    handlers: Vec<Box<dyn EventHandler>>,
+}
+
+impl EventBus {
+    /// Добавление подписчика
+    pub fn subscribe(&mut self, handler: Box<dyn EventHandler>) {
+        self.handlers.push(handler);
+    }
+
+    /// Рассылка события подписчикам в порядке подписки
+    pub fn publish(&self, event: &GameEvent) {
+        for handler in &self.handlers {
+            handler.handle(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{GameMode, GameResult};
+    use pretty_assertions::assert_eq;
+    use std::sync::{Arc, Mutex};
+
+    /// Подписчик, запоминающий полученные события
+    struct Recorder {
+        events: Arc<Mutex<Vec<GameEvent>>>,
+    }
+
+    impl EventHandler for Recorder {
+        fn handle(&self, event: &GameEvent) {
+            self.events.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn test_publish_reaches_all_subscribers() {
+        let first = Arc::new(Mutex::new(Vec::new()));
+        let second = Arc::new(Mutex::new(Vec::new()));
+        let mut bus = EventBus::default();
+        bus.subscribe(Box::new(Recorder {
+            events: Arc::clone(&first),
+        }));
+        bus.subscribe(Box::new(Recorder {
+            events: Arc::clone(&second),
+        }));
+
+        let event = GameEvent::RoundPlayed {
+            player: 7,
+            result: GameResult {
+                mode: GameMode::Exact(6),
+                roll: 6,
+                won: true,
+            },
+        };
+        bus.publish(&event);
+
+        assert_eq!(*first.lock().unwrap(), vec![event.clone()]);
+        assert_eq!(*second.lock().unwrap(), vec![event]);
+    }
+
+    #[test]
+    fn test_publish_without_subscribers() {
+        let bus = EventBus::default();
+        bus.publish(&GameEvent::StatsUpdated {
+            player: 1,
+            stats: Default::default(),
+        });
+    }
+}
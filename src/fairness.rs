@@ -0,0 +1,188 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::state::PlayerId;
+
+/// Самая длинная серия одинаковых значений подряд
+///
+/// Возвращает `(значение, длина)`; при нескольких сериях одной длины —
+/// первую из них. Для пустого среза возвращает `(0, 0)`.
+pub fn longest_run(rolls: &[u8]) -> (u8, usize) {
+    let mut best = (0, 0);
+    let mut current = (0, 0);
+    for &roll in rolls {
+        if current.1 > 0 && current.0 == roll {
+            current.1 += 1;
+        } else {
+            current = (roll, 1);
+        }
+        if current.1 > best.1 {
+            best = current;
+        }
+    }
+    best
+}
+
+/// Автокорреляция бросков с лагом 1
+///
+/// Для независимых бросков близка к 0, для повторяющихся шаблонов
+/// отклоняется к -1 или 1. Для последовательностей короче двух бросков и
+/// для постоянных последовательностей возвращает 0.0.
+pub fn lag1_autocorrelation(rolls: &[u8]) -> f64 {
+    if rolls.len() < 2 {
+        return 0.0;
+    }
+    let mean = rolls.iter().map(|&roll| roll as f64).sum::<f64>() / rolls.len() as f64;
+    let variance: f64 = rolls.iter().map(|&roll| (roll as f64 - mean).powi(2)).sum();
+    if variance == 0.0 {
+        return 0.0;
+    }
+    let covariance: f64 = rolls
+        .windows(2)
+        .map(|pair| (pair[0] as f64 - mean) * (pair[1] as f64 - mean))
+        .sum();
+    covariance / variance
+}
+
+/// Подозрительно частые броски игрока
+#[derive(Clone, Debug, PartialEq)]
+pub struct Anomaly {
+    pub player: PlayerId,
+    pub median_interval: Duration, // Медиана интервалов в окне
+}
+
+/// Детектор аномально частых бросков (защита от ботов и скриптов)
+///
+/// Для каждого игрока хранит последние `window` интервалов между бросками и
+/// сообщает об аномалии, когда окно заполнено, а медиана интервалов меньше
+/// `threshold`. Медиана не дает одной случайной паре быстрых нажатий
+/// вызвать ложное срабатывание.
+pub struct AnomalyDetector {
+    threshold: Duration,
+    window: usize,
+    players: HashMap<PlayerId, (Instant, VecDeque<Duration>)>,
+}
+
+impl AnomalyDetector {
+    pub fn new(threshold: Duration, window: usize) -> Self {
+        Self {
+            threshold,
+            window: window.max(1),
+            players: HashMap::new(),
+        }
+    }
+
+    /// Учет броска игрока в момент `now`
+    pub fn observe(&mut self, player: PlayerId, now: Instant) -> Option<Anomaly> {
+        let (last, intervals) = match self.players.get_mut(&player) {
+            Some(entry) => entry,
+            None => {
+                self.players.insert(player, (now, VecDeque::new()));
+                return None;
+            }
+        };
+
+        intervals.push_back(now.saturating_duration_since(*last));
+        *last = now;
+        if intervals.len() > self.window {
+            intervals.pop_front();
+        }
+        if intervals.len() < self.window {
+            return None;
+        }
+
+        let mut sorted: Vec<Duration> = intervals.iter().copied().collect();
+        sorted.sort();
+        let mid = sorted.len() / 2;
+        let median_interval = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2
+        } else {
+            sorted[mid]
+        };
+
+        (median_interval < self.threshold).then_some(Anomaly {
+            player,
+            median_interval,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    #[test]
+    fn test_longest_run_clear_run() {
+        assert_eq!(longest_run(&[1, 2, 6, 6, 6, 6, 3, 3]), (6, 4));
+    }
+
+    #[test]
+    fn test_longest_run_alternating() {
+        assert_eq!(longest_run(&[1, 2, 1, 2, 1]), (1, 1));
+    }
+
+    #[test]
+    fn test_longest_run_empty() {
+        assert_eq!(longest_run(&[]), (0, 0));
+    }
+
+    #[test]
+    fn test_lag1_autocorrelation_alternating() {
+        let rolls: Vec<u8> = (0..100).map(|i| if i % 2 == 0 { 1 } else { 6 }).collect();
+        assert!(lag1_autocorrelation(&rolls) < -0.9);
+    }
+
+    #[test]
+    fn test_lag1_autocorrelation_independent() {
+        let mut rng = StdRng::seed_from_u64(55);
+        let rolls: Vec<u8> = (0..10_000).map(|_| rng.gen_range(1..=6)).collect();
+        assert!(lag1_autocorrelation(&rolls).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_lag1_autocorrelation_short_sequences() {
+        assert_eq!(lag1_autocorrelation(&[]), 0.0);
+        assert_eq!(lag1_autocorrelation(&[4]), 0.0);
+        assert_eq!(lag1_autocorrelation(&[3, 3, 3]), 0.0);
+    }
+
+    #[test]
+    fn test_anomaly_detector_burst_flags() {
+        let mut detector = AnomalyDetector::new(Duration::from_millis(300), 3);
+        let start = Instant::now();
+        let mut flagged = None;
+        for i in 0..4 {
+            flagged = detector.observe(7, start + Duration::from_millis(50 * i));
+        }
+        assert_eq!(
+            flagged,
+            Some(Anomaly {
+                player: 7,
+                median_interval: Duration::from_millis(50),
+            })
+        );
+    }
+
+    #[test]
+    fn test_anomaly_detector_normal_spacing() {
+        let mut detector = AnomalyDetector::new(Duration::from_millis(300), 3);
+        let start = Instant::now();
+        for i in 0..10 {
+            let now = start + Duration::from_secs(2 * i);
+            assert_eq!(detector.observe(7, now), None);
+        }
+    }
+
+    #[test]
+    fn test_anomaly_detector_single_fast_pair_ignored() {
+        let mut detector = AnomalyDetector::new(Duration::from_millis(300), 3);
+        let start = Instant::now();
+        let offsets = [0, 2000, 2050, 4000];
+        for ms in offsets {
+            assert_eq!(detector.observe(1, start + Duration::from_millis(ms)), None);
+        }
+    }
+}
@@ -1,5 +1,57 @@
-use crate::state::{EvenOddChoice, GuessOneChoice, HighLowChoice};
-use rand::Rng;
+use crate::state::{
+    EvenOddChoice, GameMode, GameResult, GuessOneChoice, HighLowChoice, Language, ModeError,
+    ModeKind, PlayerId, SessionLog, SkillTier, SumMode, Theme, VolatilityTier,
+};
+use chrono::NaiveDate;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const WIN_MESSAGES_RU: [&str; 5] = [
+    "🎉 Поздравляю! Вы угадали!",
+    "🎊 Отлично! Правильный ответ!",
+    "✨ Великолепно! Вы победили!",
+    "🏆 Браво! Точное попадание!",
+    "🎯 Превосходно! Вы угадали!",
+];
+
+const LOSE_MESSAGES_RU: [&str; 5] = [
+    "😔 Не угадали, но не расстраивайтесь!",
+    "🎲 В этот раз не повезло, попробуйте еще!",
+    "💪 Ничего страшного, удача улыбнется в следующий раз!",
+    "🌟 Не переживайте, у вас все получится!",
+    "🎮 Попытка не пытка, играем еще!",
+];
+
+const WIN_MESSAGES_EN: [&str; 5] = [
+    "🎉 Congratulations! You guessed it!",
+    "🎊 Great! That's the right answer!",
+    "✨ Brilliant! You won!",
+    "🏆 Bravo! Spot on!",
+    "🎯 Excellent! You guessed it!",
+];
+
+const LOSE_MESSAGES_EN: [&str; 5] = [
+    "😔 Not this time, but don't be upset!",
+    "🎲 No luck this round, try again!",
+    "💪 No worries, luck will smile on you next time!",
+    "🌟 Don't worry, you'll get it!",
+    "🎮 Nothing ventured, nothing gained — let's play again!",
+];
+
+/// Максимальное расстояние до джекпота, при котором показывается "почти!"
+const TEASER_MAX_DISTANCE: u8 = 1;
+
+/// Предельное число удвоений, которое советует `optimal_stop_round`
+const MAX_RECOMMENDED_DOUBLINGS: u32 = 20;
+
+/// Предел раундов одной симуляции в `expected_rounds_until_broke`
+const MAX_SIMULATED_ROUNDS: u32 = 10_000;
+
+/// Минимум игр, после которого присваиваются уровни выше `Novice`
+const SKILL_TIER_MIN_GAMES: u64 = 50;
 
 /// Структура для управления игровой логикой
 pub struct DiceGame;
@@ -7,7 +59,7 @@ pub struct DiceGame;
 impl DiceGame {
     /// Проверка результата для игры "Четное/Нечетное"
     pub fn check_even_odd(dice_result: u8, user_choice: EvenOddChoice) -> bool {
-        let is_even = dice_result % 2 == 0;
+        let is_even = dice_result.is_multiple_of(2);
         match user_choice {
             EvenOddChoice::Even => is_even,
             EvenOddChoice::Odd => !is_even,
@@ -27,6 +79,70 @@ impl DiceGame {
         dice_result == user_guess
     }
 
+    /// Проверка попадания в окно допуска вокруг загаданного числа
+    ///
+    /// При `tolerance == 0` совпадает с проверкой точного числа.
+    pub fn check_within(dice_result: u8, user_guess: u8, tolerance: u8) -> bool {
+        dice_result.abs_diff(user_guess) <= tolerance
+    }
+
+    /// Оценка близости догадки для игры "Точное число" с частичными очками
+    ///
+    /// Возвращает 1.0 при точном попадании и линейно убывает до 0.0
+    /// на максимально возможном расстоянии `sides - 1`.
+    pub fn near_miss_score(dice_result: u8, user_guess: u8, sides: u8) -> f64 {
+        let distance = dice_result.abs_diff(user_guess);
+        if distance == 0 {
+            return 1.0;
+        }
+        if sides <= 1 {
+            return 0.0;
+        }
+        let max_distance = (sides - 1) as f64;
+        (1.0 - distance as f64 / max_distance).max(0.0)
+    }
+
+    /// Выплата за "Больше/Меньше" с учетом запаса над порогом
+    ///
+    /// Ставка "больше" выигрывает при `dice > threshold`, "меньше" — при
+    /// `dice <= threshold`, так что порог 3 на d6 делит грани как в
+    /// `check_high_low`. Выплата равна `base`, умноженному на долю запаса
+    /// от максимально возможного; при проигрыше 0.0.
+    pub fn margin_payout(
+        dice_result: u8,
+        threshold: u8,
+        predict_over: bool,
+        base: f64,
+        sides: u8,
+    ) -> f64 {
+        let (margin, max_margin) = if predict_over {
+            if dice_result <= threshold {
+                return 0.0;
+            }
+            (dice_result - threshold, sides.saturating_sub(threshold))
+        } else {
+            if dice_result > threshold || dice_result == 0 {
+                return 0.0;
+            }
+            (threshold - dice_result + 1, threshold)
+        };
+        if max_margin == 0 {
+            return 0.0;
+        }
+        base * (margin.min(max_margin) as f64 / max_margin as f64)
+    }
+
+    /// Проверка комбинированной ставки "четность И диапазон"
+    ///
+    /// На d6 комбинации неравновероятны: "четное и больше" (4, 6) и
+    /// "нечетное и меньше" (1, 3) выигрывают с вероятностью 1/3, а
+    /// "четное и меньше" (2) и "нечетное и больше" (5) — 1/6. В среднем это
+    /// 1/4, поэтому единый множитель 4x честен только в среднем; точные
+    /// справедливые множители — 3x и 6x.
+    pub fn check_combined(dice_result: u8, parity: EvenOddChoice, range: HighLowChoice) -> bool {
+        Self::check_even_odd(dice_result, parity) && Self::check_high_low(dice_result, range)
+    }
+
     /// Проверка результата для игры "Угадать единицу"
     pub fn check_guess_one(dice_result: u8, user_choice: GuessOneChoice) -> bool {
         let is_one = dice_result == 1;
@@ -36,123 +152,2787 @@ impl DiceGame {
         }
     }
 
+    /// Проверка результата для игры "Четность суммы двух кубиков"
+    ///
+    /// Для кубика с четным числом граней четная и нечетная сумма выпадают
+    /// с вероятностью ровно 50/50 независимо от размера кубика.
+    pub fn check_sum_parity(die_a: u8, die_b: u8, choice: EvenOddChoice) -> bool {
+        // Складываем только остатки, чтобы избежать переполнения u8
+        Self::check_even_odd(die_a % 2 + die_b % 2, choice)
+    }
+
+    /// Проверка угадывания набора значений нескольких кубиков без учета порядка
+    ///
+    /// Если количество кубиков и догадок различается, это проигрыш.
+    pub fn check_multiset(dice: &[u8], guess: &[u8]) -> bool {
+        if dice.len() != guess.len() {
+            return false;
+        }
+        let mut dice_sorted = dice.to_vec();
+        let mut guess_sorted = guess.to_vec();
+        dice_sorted.sort_unstable();
+        guess_sorted.sort_unstable();
+        dice_sorted == guess_sorted
+    }
+
+    /// Проверка результата для любого режима на кубике с `sides` гранями
+    ///
+    /// "Больше" означает верхнюю половину граней: для d6 это 4-6, как и в
+    /// `check_high_low`.
+    pub fn check_mode(mode: &GameMode, dice_result: u8, sides: u8) -> bool {
+        match mode {
+            GameMode::EvenOdd(choice) => Self::check_even_odd(dice_result, choice.clone()),
+            GameMode::HighLow(choice) => {
+                let is_high = dice_result as u16 * 2 > sides as u16;
+                match choice {
+                    HighLowChoice::High => is_high,
+                    HighLowChoice::Low => !is_high,
+                }
+            }
+            GameMode::Exact(guess) => Self::check_exact_number(dice_result, *guess),
+            GameMode::GuessOne(choice) => Self::check_guess_one(dice_result, choice.clone()),
+        }
+    }
+
+    /// Розыгрыш одного раунда на кубике с `sides` гранями
+    pub fn play_round(mode: &GameMode, sides: u8, rng: &mut impl Rng) -> GameResult {
+        let roll = rng.gen_range(1..=sides.max(1));
+        GameResult {
+            mode: mode.clone(),
+            roll,
+            won: Self::check_mode(mode, roll, sides),
+        }
+    }
+
+    /// Разделение одной ставки между несколькими режимами в одном раунде
+    ///
+    /// Доли `allocations` должны в сумме давать `total`, а режимы — быть
+    /// допустимыми для кубика с хотя бы одной гранью. Кубик бросается
+    /// один раз, и каждая доля рассчитывается по этому броску независимо:
+    /// выигрыш равен доле, умноженной на справедливый множитель режима,
+    /// с округлением вниз. Выплата считается в целых числах, чтобы ошибка
+    /// плавающей точки не занижала ее на единицу.
+    pub fn split_bet(
+        total: u64,
+        allocations: &[(GameMode, u64)],
+        sides: u8,
+        rng: &mut impl Rng,
+    ) -> Result<Vec<BetSettlement>, BetError> {
+        if sides == 0 {
+            return Err(BetError::NoSides);
+        }
+        let allocated: u128 = allocations.iter().map(|(_, stake)| *stake as u128).sum();
+        if allocated != total as u128 {
+            return Err(BetError::AllocationMismatch { total, allocated });
+        }
+        for (mode, _) in allocations {
+            mode.validate(sides).map_err(BetError::InvalidMode)?;
+        }
+
+        let roll = rng.gen_range(1..=sides);
+        Ok(allocations
+            .iter()
+            .map(|(mode, stake)| {
+                let won = Self::check_mode(mode, roll, sides);
+                let payout = if won {
+                    let winning = Self::winning_faces(mode, sides).len() as u128;
+                    u64::try_from(*stake as u128 * sides as u128 / winning).unwrap_or(u64::MAX)
+                } else {
+                    0
+                };
+                BetSettlement {
+                    result: GameResult {
+                        mode: mode.clone(),
+                        roll,
+                        won,
+                    },
+                    stake: *stake,
+                    payout,
+                }
+            })
+            .collect())
+    }
+
+    /// Генератор бросков сессии
+    ///
+    /// ChaCha8 выдает одинаковую последовательность во всех версиях,
+    /// поэтому сохраненная сессия воспроизводится точно.
+    pub fn session_rng(seed: u64) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(seed)
+    }
+
+    /// Воспроизведение сессии по журналу
+    pub fn replay(log: &SessionLog) -> Vec<GameResult> {
+        let mut rng = Self::session_rng(log.seed);
+        log.modes
+            .iter()
+            .map(|mode| Self::play_round(mode, log.sides, &mut rng))
+            .collect()
+    }
+
+    /// Все грани кубика, при которых выбранный режим выигрывает
+    pub fn winning_faces(mode: &GameMode, sides: u8) -> Vec<u8> {
+        (1..=sides)
+            .filter(|&face| Self::check_mode(mode, face, sides))
+            .collect()
+    }
+
+    /// Вероятность выигрыша режима на честном кубике с `sides` гранями
+    pub fn win_probability(mode: &GameMode, sides: u8) -> f64 {
+        if sides == 0 {
+            return 0.0;
+        }
+        Self::winning_faces(mode, sides).len() as f64 / sides as f64
+    }
+
+    /// Дисперсия выплаты на единицу ставки при справедливом множителе
+    ///
+    /// Выплата равна `1 / p` с вероятностью `p` и 0 иначе, поэтому
+    /// дисперсия равна `(1 - p) / p`. Для режимов, которые не могут
+    /// выиграть, возвращает 0.0.
+    pub fn payout_variance(mode: &GameMode, sides: u8) -> f64 {
+        let probability = Self::win_probability(mode, sides);
+        if probability == 0.0 {
+            return 0.0;
+        }
+        (1.0 - probability) / probability
+    }
+
+    /// Оценка "азартности" режима для сравнения игр между собой
+    ///
+    /// Равна дисперсии выплаты при справедливом множителе: редкие крупные
+    /// выигрыши (точное число) оцениваются выше частых мелких (четность).
+    pub fn excitement_score(mode: &GameMode, sides: u8) -> f64 {
+        Self::payout_variance(mode, sides)
+    }
+
+    /// Вероятность выигрыша ставки на сумму, когда первый кубик уже открыт
+    ///
+    /// Для анимации раскрытия: перебирает грани второго кубика и считает
+    /// долю тех, при которых сумма выигрывает.
+    pub fn conditional_win_probability(mode: &SumMode, revealed_die: u8, sides: u8) -> f64 {
+        if sides == 0 {
+            return 0.0;
+        }
+        let winning = (1..=sides)
+            .filter(|&second| mode.wins(revealed_die as u16 + second as u16))
+            .count();
+        winning as f64 / sides as f64
+    }
+
+    /// Сложность режима для подбора соперников по шкале 1–10
+    ///
+    /// В отличие от `volatility_tier` оценивает не разброс выплат, а то,
+    /// насколько режим подходит новичку: четность проще всего объяснить
+    /// и выиграть, точное число — сложнее всего.
+    pub fn difficulty_rating(mode_kind: ModeKind) -> u8 {
+        match mode_kind {
+            ModeKind::EvenOdd => 2,
+            ModeKind::HighLow => 3,
+            ModeKind::GuessOne => 6,
+            ModeKind::Exact => 8,
+        }
+    }
+
+    /// Уровень волатильности режима по дисперсии выплаты
+    ///
+    /// Дисперсия до 1.0 включительно (вероятность выигрыша от 1/2) — низкая,
+    /// до 3.0 включительно (вероятность от 1/4) — средняя, выше — высокая.
+    /// На d6 четность и диапазон дают 1.0, точное число — 5.0.
+    pub fn volatility_tier(mode: &GameMode, sides: u8) -> VolatilityTier {
+        let variance = Self::payout_variance(mode, sides);
+        if variance <= 1.0 {
+            VolatilityTier::Low
+        } else if variance <= 3.0 {
+            VolatilityTier::Medium
+        } else {
+            VolatilityTier::High
+        }
+    }
+
+    /// Распределение исходов режима: вероятности выигрыша и проигрыша
+    ///
+    /// Все режимы `GameMode` бинарные, поэтому исходов всегда два,
+    /// и их вероятности в сумме дают 1.0.
+    pub fn outcome_distribution(mode: &GameMode, sides: u8) -> Vec<(bool, f64)> {
+        let probability = Self::win_probability(mode, sides);
+        vec![(true, probability), (false, 1.0 - probability)]
+    }
+
+    /// Справедливый множитель выплаты для режима (`1 / p`)
+    ///
+    /// Возвращает `None`, если режим не может выиграть на таком кубике.
+    pub fn fair_multiplier(mode: &GameMode, sides: u8) -> Option<f64> {
+        let probability = Self::win_probability(mode, sides);
+        if probability == 0.0 {
+            return None;
+        }
+        Some(1.0 / probability)
+    }
+
+    /// Справедливый множитель экспресса из нескольких ставок
+    ///
+    /// Экспресс выигрывает, только если выиграли все ставки, поэтому
+    /// множитель равен произведению справедливых множителей ставок. Пустой
+    /// экспресс дает 1.0; ставка, которая не может выиграть, — бесконечность.
+    pub fn parlay_fair_multiplier(modes: &[GameMode], sides: u8) -> f64 {
+        let probability: f64 = modes
+            .iter()
+            .map(|mode| Self::win_probability(mode, sides))
+            .product();
+        1.0 / probability
+    }
+
+    /// Множитель экспресса с преимуществом заведения
+    ///
+    /// Преимущество `house_edge` (ограничено `0.0..=1.0`) удерживается
+    /// один раз со всего экспресса, а не с каждой ставки.
+    pub fn parlay_multiplier_with_edge(modes: &[GameMode], sides: u8, house_edge: f64) -> f64 {
+        Self::parlay_fair_multiplier(modes, sides) * (1.0 - house_edge.clamp(0.0, 1.0))
+    }
+
+    /// Все возможные выборы пользователя для вида игры
+    pub fn mode_choices(kind: ModeKind, sides: u8) -> Vec<GameMode> {
+        match kind {
+            ModeKind::EvenOdd => EvenOddChoice::all().map(GameMode::EvenOdd).to_vec(),
+            ModeKind::HighLow => HighLowChoice::all().map(GameMode::HighLow).to_vec(),
+            ModeKind::Exact => (1..=sides.max(1)).map(GameMode::Exact).collect(),
+            ModeKind::GuessOne => GuessOneChoice::all().map(GameMode::GuessOne).to_vec(),
+        }
+    }
+
+    /// Таблица вероятностей и справедливых множителей всех режимов
+    ///
+    /// Для справки в чате: по строке на каждый выбор каждого вида игры, с
+    /// названием выбора на языке `lang`. Все грани точного числа
+    /// равновероятны, поэтому оно представлено одной строкой.
+    pub fn odds_table(sides: u8, lang: Language) -> Vec<OddsRow> {
+        ModeKind::all()
+            .into_iter()
+            .flat_map(|kind| {
+                let mut modes = Self::mode_choices(kind, sides);
+                if kind == ModeKind::Exact {
+                    modes.truncate(1);
+                }
+                modes
+            })
+            .map(|mode| OddsRow {
+                name: Self::choice_name(&mode, lang),
+                win_probability: Self::win_probability(&mode, sides),
+                fair_multiplier: Self::fair_multiplier(&mode, sides),
+                mode,
+            })
+            .collect()
+    }
+
+    /// Энтропия равномерного выбора внутри вида игры, в битах
+    ///
+    /// Равна `log2` числа различных выборов: 1 бит для четности, `log2(sides)`
+    /// для точного числа. Если выбирать не из чего, возвращает 0.0.
+    pub fn choice_entropy(mode_kind: ModeKind, sides: u8) -> f64 {
+        let choices = Self::mode_choices(mode_kind, sides).len();
+        if choices <= 1 {
+            return 0.0;
+        }
+        (choices as f64).log2()
+    }
+
+    /// Выбор с наибольшей вероятностью выигрыша на неравномерном кубике
+    ///
+    /// `weights[i]` — вес грани `i + 1`, число граней равно длине среза.
+    /// При равных шансах возвращается первый из вариантов.
+    pub fn optimal_choice(weights: &[f64], mode_kind: ModeKind) -> GameMode {
+        let sides = u8::try_from(weights.len()).unwrap_or(u8::MAX);
+        let win_weight = |mode: &GameMode| -> f64 {
+            Self::winning_faces(mode, sides)
+                .iter()
+                .map(|&face| weights[face as usize - 1])
+                .sum()
+        };
+
+        let mut best: Option<(GameMode, f64)> = None;
+        for mode in Self::mode_choices(mode_kind, sides) {
+            let weight = win_weight(&mode);
+            if best
+                .as_ref()
+                .is_none_or(|(_, best_weight)| weight > *best_weight)
+            {
+                best = Some((mode, weight));
+            }
+        }
+        // mode_choices всегда возвращает хотя бы один вариант
+        best.map(|(mode, _)| mode).unwrap()
+    }
+
     /// Получение сообщения о выигрыше
     pub fn win_message() -> &'static str {
-        let messages = [
-            "🎉 Поздравляю! Вы угадали!",
-            "🎊 Отлично! Правильный ответ!",
-            "✨ Великолепно! Вы победили!",
-            "🏆 Браво! Точное попадание!",
-            "🎯 Превосходно! Вы угадали!",
-        ];
-        let mut rng = rand::thread_rng();
-        let index = rng.gen_range(0..messages.len());
-        messages[index]
+        Self::outcome_message(true, Language::Ru, &mut rand::thread_rng())
+    }
+
+    /// Сообщение о выигрыше в персональном для игрока порядке
+    ///
+    /// Порядок сообщений — перестановка, заданная зерном из `player`, а
+    /// `nonce` (например, номер раунда) выбирает позицию в ней. Двое игроков
+    /// в одном чате видят разные последовательности, а одна и та же пара
+    /// игрок/nonce всегда дает одно и то же сообщение.
+    pub fn win_message_for(player: PlayerId, nonce: u64) -> &'static str {
+        Self::personal_message(true, Language::Ru, player, nonce)
+    }
+
+    /// Сообщение о результате в персональном для игрока порядке
+    fn personal_message(won: bool, lang: Language, player: PlayerId, nonce: u64) -> &'static str {
+        let messages = Self::message_pool(won, lang);
+        let mut order: Vec<usize> = (0..messages.len()).collect();
+        order.shuffle(&mut ChaCha8Rng::seed_from_u64(player));
+        messages[order[(nonce % order.len() as u64) as usize]]
     }
 
     /// Получение ободряющего сообщения при проигрыше
     pub fn lose_message() -> &'static str {
-        let messages = [
-            "😔 Не угадали, но не расстраивайтесь!",
-            "🎲 В этот раз не повезло, попробуйте еще!",
-            "💪 Ничего страшного, удача улыбнется в следующий раз!",
-            "🌟 Не переживайте, у вас все получится!",
-            "🎮 Попытка не пытка, играем еще!",
-        ];
-        let mut rng = rand::thread_rng();
-        let index = rng.gen_range(0..messages.len());
-        messages[index]
+        Self::outcome_message(false, Language::Ru, &mut rand::thread_rng())
+    }
+
+    /// Получение сообщения о выигрыше или проигрыше на выбранном языке
+    pub fn outcome_message(won: bool, lang: Language, rng: &mut impl Rng) -> &'static str {
+        let messages = Self::message_pool(won, lang);
+        messages[rng.gen_range(0..messages.len())]
+    }
+
+    /// Сообщение "почти получилось!" при промахе мимо редкого исхода
+    ///
+    /// Возвращает сообщение, только если промах не дальше
+    /// `TEASER_MAX_DISTANCE`; точное попадание (расстояние 0) и дальние
+    /// промахи дают `None`.
+    pub fn teaser_message(distance_to_jackpot: u8, lang: Language) -> Option<&'static str> {
+        if distance_to_jackpot == 0 || distance_to_jackpot > TEASER_MAX_DISTANCE {
+            return None;
+        }
+        Some(match lang {
+            Language::Ru => "🔥 Совсем чуть-чуть! Джекпот был рядом!",
+            Language::En => "🔥 So close! The jackpot was right there!",
+        })
+    }
+
+    /// Набор сообщений для исхода раунда на выбранном языке
+    fn message_pool(won: bool, lang: Language) -> &'static [&'static str] {
+        match (won, lang) {
+            (true, Language::Ru) => &WIN_MESSAGES_RU,
+            (false, Language::Ru) => &LOSE_MESSAGES_RU,
+            (true, Language::En) => &WIN_MESSAGES_EN,
+            (false, Language::En) => &LOSE_MESSAGES_EN,
+        }
+    }
+
+    /// Форматирование суммы выигрыша по правилам языка
+    ///
+    /// Русский: `1 000 ₽`, английский: `$1,000`.
+    pub fn format_currency(amount: u64, locale: Language) -> String {
+        match locale {
+            Language::Ru => format!("{} ₽", Self::group_thousands(amount, ' ')),
+            Language::En => format!("${}", Self::group_thousands(amount, ',')),
+        }
+    }
+
+    /// Форматирование вероятности в проценты с одним знаком после запятой
+    ///
+    /// Русский: `16,7 %`, английский: `16.7%`. Вероятность ограничивается
+    /// `0.0..=1.0`, NaN выводится как 0.
+    pub fn format_probability(p: f64, lang: Language) -> String {
+        let p = if p.is_nan() { 0.0 } else { p.clamp(0.0, 1.0) };
+        let percent = format!("{:.1}", p * 100.0);
+        match lang {
+            Language::Ru => format!("{} %", percent.replace('.', ",")),
+            Language::En => format!("{}%", percent),
+        }
+    }
+
+    /// Разбиение числа на группы по три цифры
+    fn group_thousands(amount: u64, separator: char) -> String {
+        let digits = amount.to_string();
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index).is_multiple_of(3) {
+                grouped.push(separator);
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    /// Задержки между кадрами анимации броска с замедлением к концу
+    ///
+    /// Задержки в сумме дают ровно `total_ms`. Если `total_ms` не меньше
+    /// `count * (count + 1) / 2`, каждый кадр получает ненулевую задержку и
+    /// задержки строго растут: кадр `i` получает `i` мс плюс квадратичную
+    /// долю остатка. При меньшем бюджете строгий рост невозможен, и
+    /// задержки лишь не убывают (первые кадры могут быть нулевыми).
+    /// Остаток от округления раздается по 1 мс с последних кадров.
+    pub fn frame_delays(count: usize, total_ms: u64) -> Vec<u64> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let ramp_sum = count as u64 * (count as u64 + 1) / 2;
+        let (mut delays, budget): (Vec<u64>, u64) = if total_ms >= ramp_sum {
+            ((1..=count as u64).collect(), total_ms - ramp_sum)
+        } else {
+            (vec![0; count], total_ms)
+        };
+
+        let weights: Vec<u64> = (1..=count as u64).map(|i| i * i).collect();
+        let weight_sum: u64 = weights.iter().sum();
+        for (delay, weight) in delays.iter_mut().zip(&weights) {
+            *delay += (budget as u128 * *weight as u128 / weight_sum as u128) as u64;
+        }
+        // Сумма округлений вниз теряет меньше `count` мс
+        let assigned: u64 = delays.iter().sum();
+        let remainder = (total_ms - assigned) as usize;
+        for delay in delays.iter_mut().rev().take(remainder) {
+            *delay += 1;
+        }
+        delays
+    }
+
+    /// Название режима для меню на выбранном языке
+    pub fn mode_name(kind: ModeKind, lang: Language) -> &'static str {
+        match (kind, lang) {
+            (ModeKind::EvenOdd, Language::Ru) => "Четное/Нечетное",
+            (ModeKind::EvenOdd, Language::En) => "Even/Odd",
+            (ModeKind::HighLow, Language::Ru) => "Больше/Меньше",
+            (ModeKind::HighLow, Language::En) => "High/Low",
+            (ModeKind::Exact, Language::Ru) => "Точное число",
+            (ModeKind::Exact, Language::En) => "Exact number",
+            (ModeKind::GuessOne, Language::Ru) => "Угадать единицу",
+            (ModeKind::GuessOne, Language::En) => "Guess the one",
+        }
+    }
+
+    /// Название конкретного выбора внутри режима на выбранном языке
+    ///
+    /// Для точного числа возвращает название вида игры: все его выборы
+    /// отличаются только загаданной гранью.
+    pub fn choice_name(mode: &GameMode, lang: Language) -> &'static str {
+        match (mode, lang) {
+            (GameMode::EvenOdd(EvenOddChoice::Even), Language::Ru) => "Четное",
+            (GameMode::EvenOdd(EvenOddChoice::Even), Language::En) => "Even",
+            (GameMode::EvenOdd(EvenOddChoice::Odd), Language::Ru) => "Нечетное",
+            (GameMode::EvenOdd(EvenOddChoice::Odd), Language::En) => "Odd",
+            (GameMode::HighLow(HighLowChoice::High), Language::Ru) => "Больше",
+            (GameMode::HighLow(HighLowChoice::High), Language::En) => "High",
+            (GameMode::HighLow(HighLowChoice::Low), Language::Ru) => "Меньше",
+            (GameMode::HighLow(HighLowChoice::Low), Language::En) => "Low",
+            (GameMode::GuessOne(GuessOneChoice::Yes), Language::Ru) => "Выпадет единица",
+            (GameMode::GuessOne(GuessOneChoice::Yes), Language::En) => "A one",
+            (GameMode::GuessOne(GuessOneChoice::No), Language::Ru) => "Не единица",
+            (GameMode::GuessOne(GuessOneChoice::No), Language::En) => "Not a one",
+            (GameMode::Exact(_), _) => Self::mode_name(ModeKind::Exact, lang),
+        }
+    }
+
+    /// Короткая карточка результата, которой можно поделиться в чате
+    ///
+    /// Содержит игрока, режим, выпавшее число и исход; множитель выигрыша
+    /// считается для обычного кубика d6, как и в `rules`.
+    pub fn share_card(result: &GameResult, player: &str, lang: Language) -> String {
+        let mode = Self::mode_name(result.mode.kind(), lang);
+        let payout = Self::fair_multiplier(&result.mode, 6)
+            .map(Self::format_multiplier)
+            .unwrap_or_default();
+        match (result.won, lang) {
+            (true, Language::Ru) => format!(
+                "🎲 Игрок {} · {} · выпало {} — выиграл {}!",
+                player, mode, result.roll, payout
+            ),
+            (false, Language::Ru) => format!(
+                "🎲 Игрок {} · {} · выпало {} — проиграл",
+                player, mode, result.roll
+            ),
+            (true, Language::En) => format!(
+                "🎲 Player {} · {} · rolled {} — won {}!",
+                player, mode, result.roll, payout
+            ),
+            (false, Language::En) => format!(
+                "🎲 Player {} · {} · rolled {} — lost",
+                player, mode, result.roll
+            ),
+        }
+    }
+
+    /// Правила режима на выбранном языке для справки в чате
+    ///
+    /// Выплаты рассчитываются для обычного кубика d6 по справедливому
+    /// множителю.
+    pub fn rules(mode_kind: ModeKind, lang: Language) -> String {
+        let payout = |mode: GameMode| {
+            Self::fair_multiplier(&mode, 6)
+                .map(Self::format_multiplier)
+                .unwrap_or_default()
+        };
+        match (mode_kind, lang) {
+            (ModeKind::EvenOdd, Language::Ru) => format!(
+                "Угадайте, будет ли выпавшее число четным или нечетным. \
+                 Вы выигрываете, если угадали четность. Выплата: {}.",
+                payout(GameMode::EvenOdd(EvenOddChoice::Even))
+            ),
+            (ModeKind::EvenOdd, Language::En) => format!(
+                "Guess whether the roll will be even or odd. \
+                 You win if you guessed the parity. Payout: {}.",
+                payout(GameMode::EvenOdd(EvenOddChoice::Even))
+            ),
+            (ModeKind::HighLow, Language::Ru) => format!(
+                "Угадайте, будет ли результат больше 3.5 (4-6) или меньше 3.5 (1-3). \
+                 Вы выигрываете, если угадали диапазон. Выплата: {}.",
+                payout(GameMode::HighLow(HighLowChoice::High))
+            ),
+            (ModeKind::HighLow, Language::En) => format!(
+                "Guess whether the roll will be above 3.5 (4-6) or below 3.5 (1-3). \
+                 You win if you guessed the range. Payout: {}.",
+                payout(GameMode::HighLow(HighLowChoice::High))
+            ),
+            (ModeKind::Exact, Language::Ru) => format!(
+                "Загадайте число от 1 до 6. \
+                 Вы выигрываете, если выпало именно это число. Выплата: {}.",
+                payout(GameMode::Exact(1))
+            ),
+            (ModeKind::Exact, Language::En) => format!(
+                "Pick a number from 1 to 6. \
+                 You win if exactly that number is rolled. Payout: {}.",
+                payout(GameMode::Exact(1))
+            ),
+            (ModeKind::GuessOne, Language::Ru) => format!(
+                "Угадайте, выпадет ли единица. \
+                 Вы выигрываете, если ответ верный. Выплата: {} за «да», {} за «нет».",
+                payout(GameMode::GuessOne(GuessOneChoice::Yes)),
+                payout(GameMode::GuessOne(GuessOneChoice::No))
+            ),
+            (ModeKind::GuessOne, Language::En) => format!(
+                "Guess whether a one will be rolled. \
+                 You win if your answer is right. Payout: {} for \"yes\", {} for \"no\".",
+                payout(GameMode::GuessOne(GuessOneChoice::Yes)),
+                payout(GameMode::GuessOne(GuessOneChoice::No))
+            ),
+        }
+    }
+
+    /// Множитель выплаты в виде `×2` или `×1.2`
+    fn format_multiplier(multiplier: f64) -> String {
+        let rounded = (multiplier * 10.0).round() / 10.0;
+        if rounded.fract() == 0.0 {
+            format!("×{}", rounded as u64)
+        } else {
+            format!("×{:.1}", rounded)
+        }
+    }
+
+    /// Исход дуэли двух бросков без текста результата
+    ///
+    /// `Greater` — победил компьютер (`bot_dice`), `Less` — пользователь,
+    /// `Equal` — ничья. Все сравнения двух игроков опираются на эту функцию.
+    pub fn duel_outcome(bot_dice: u8, user_dice: u8) -> Ordering {
+        bot_dice.cmp(&user_dice)
     }
 
     /// Сравнение результатов бросков кубиков
     pub fn compare_dices(bot_dice: u8, user_dice: u8) -> &'static str {
-        if bot_dice > user_dice {
-            "🤖 Компьютер победил!"
-        } else if user_dice > bot_dice {
-            "🎉 Пользователь победил!"
+        Self::compare_dices_themed(bot_dice, user_dice, Theme::default())
+    }
+
+    /// Сравнение результатов бросков кубиков с выбранным оформлением
+    pub fn compare_dices_themed(bot_dice: u8, user_dice: u8, theme: Theme) -> &'static str {
+        Self::verdict(Self::duel_outcome(bot_dice, user_dice), theme)
+    }
+
+    /// Игрок против лучшего из трех кубиков заведения
+    ///
+    /// Игрок побеждает, если его бросок не меньше максимума заведения,
+    /// ничьи нет. На d6 вероятность победы игрока 441/1296 ≈ 34%, поэтому
+    /// справедливый множитель выплаты ≈ 2.94.
+    pub fn challenge_house(player: u8, house_rolls: &[u8; 3]) -> &'static str {
+        let house_best = house_rolls.iter().copied().max().unwrap_or(0);
+        let ordering = if player >= house_best {
+            Ordering::Less
         } else {
-            "🤝 Ничья!"
+            Ordering::Greater
+        };
+        Self::verdict(ordering, Theme::Emoji)
+    }
+
+    /// Текст результата по сравнению броска компьютера с броском пользователя
+    fn verdict(ordering: Ordering, theme: Theme) -> &'static str {
+        match (ordering, theme) {
+            (Ordering::Greater, Theme::Emoji) => "🤖 Компьютер победил!",
+            (Ordering::Less, Theme::Emoji) => "🎉 Пользователь победил!",
+            (Ordering::Equal, Theme::Emoji) => "🤝 Ничья!",
+            (Ordering::Greater, Theme::PlainText) => "Computer won",
+            (Ordering::Less, Theme::PlainText) => "User won",
+            (Ordering::Equal, Theme::PlainText) => "Draw",
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::state::{EvenOddChoice, GuessOneChoice, HighLowChoice};
-    use pretty_assertions::assert_eq;
+    /// Определение победителей среди нескольких игроков
+    ///
+    /// Возвращает всех игроков с наибольшим броском (несколько при ничьей
+    /// за первое место) в исходном порядке.
+    pub fn compare_many(rolls: &[(PlayerId, u8)]) -> Vec<PlayerId> {
+        let Some(best) = rolls.iter().map(|&(_, roll)| roll).max() else {
+            return Vec::new();
+        };
+        rolls
+            .iter()
+            .filter(|&&(_, roll)| roll == best)
+            .map(|&(player, _)| player)
+            .collect()
+    }
 
-    #[test]
-    fn test_check_even_odd_basic() {
-        assert!(DiceGame::check_even_odd(2, EvenOddChoice::Even));
-        assert!(!DiceGame::check_even_odd(2, EvenOddChoice::Odd));
-        assert!(DiceGame::check_even_odd(5, EvenOddChoice::Odd));
-        assert!(!DiceGame::check_even_odd(5, EvenOddChoice::Even));
+    /// Сравнение бросков с перебросом обоих кубиков при ничьей
+    ///
+    /// Кубики перебрасываются на `sides` гранях не более `max_attempts`
+    /// раз, после чего засчитывается ничья.
+    pub fn compare_dices_reroll_bounded(
+        bot_dice: u8,
+        user_dice: u8,
+        sides: u8,
+        max_attempts: u32,
+        rng: &mut impl Rng,
+    ) -> &'static str {
+        let (mut bot_dice, mut user_dice) = (bot_dice, user_dice);
+        let mut attempts = 0;
+        while bot_dice == user_dice && attempts < max_attempts {
+            bot_dice = rng.gen_range(1..=sides.max(1));
+            user_dice = rng.gen_range(1..=sides.max(1));
+            attempts += 1;
+        }
+        Self::compare_dices(bot_dice, user_dice)
     }
 
-    #[test]
-    fn test_check_high_low_basic() {
-        assert!(DiceGame::check_high_low(1, HighLowChoice::Low));
-        assert!(DiceGame::check_high_low(3, HighLowChoice::Low));
-        assert!(!DiceGame::check_high_low(3, HighLowChoice::High));
-        assert!(DiceGame::check_high_low(4, HighLowChoice::High));
-        assert!(DiceGame::check_high_low(6, HighLowChoice::High));
-        assert!(!DiceGame::check_high_low(4, HighLowChoice::Low));
+    /// Оценка справедливого множителя выплаты по наблюдаемым результатам
+    ///
+    /// Возвращает `total / wins` — выплату, при которой игра была бы честной
+    /// с учетом фактической частоты выигрышей. Если выигрышей не было,
+    /// возвращает `None`.
+    pub fn implied_multiplier(wins: u64, total: u64) -> Option<f64> {
+        if wins == 0 {
+            return None;
+        }
+        Some(total as f64 / wins as f64)
     }
 
-    #[test]
-    fn test_check_exact_number_basic() {
-        assert!(DiceGame::check_exact_number(4, 4));
-        assert!(!DiceGame::check_exact_number(1, 6));
+    /// Доверительный интервал Уилсона для доли выигрышей
+    ///
+    /// `z` — квантиль нормального распределения (1.96 для 95%).
+    /// Без сыгранных игр возвращает `(0.0, 0.0)`.
+    pub fn win_rate_ci(wins: u64, total: u64, z: f64) -> (f64, f64) {
+        if total == 0 {
+            return (0.0, 0.0);
+        }
+        let n = total as f64;
+        let p = (wins as f64 / n).min(1.0);
+        let z2 = z * z;
+        let denominator = 1.0 + z2 / n;
+        let center = (p + z2 / (2.0 * n)) / denominator;
+        let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+        ((center - margin).max(0.0), (center + margin).min(1.0))
     }
 
-    #[test]
-    fn test_check_guess_one_basic() {
-        assert!(DiceGame::check_guess_one(1, GuessOneChoice::Yes));
-        assert!(!DiceGame::check_guess_one(1, GuessOneChoice::No));
-        assert!(DiceGame::check_guess_one(3, GuessOneChoice::No));
-        assert!(!DiceGame::check_guess_one(3, GuessOneChoice::Yes));
+    /// Преимущество заведения при заданном множителе выплаты
+    ///
+    /// Справедливый множитель дает 0.0, заниженный — положительное
+    /// преимущество.
+    pub fn house_edge(win_prob: f64, multiplier: f64) -> f64 {
+        1.0 - win_prob * multiplier
     }
 
-    #[test]
-    fn test_compare_dices_bot_wins() {
-        assert_eq!(DiceGame::compare_dices(5, 3), "🤖 Компьютер победил!");
+    /// Доля выигрышей, необходимая для выхода в ноль при данном множителе
+    ///
+    /// Для множителя 2x это 0.5, для 6x ≈ 0.167. При множителе не больше
+    /// 1.0 прибыль невозможна даже при выигрыше каждого раунда, и функция
+    /// возвращает 1.0.
+    pub fn break_even_win_rate(multiplier: f64) -> f64 {
+        if multiplier <= 1.0 || multiplier.is_nan() {
+            return 1.0;
+        }
+        1.0 / multiplier
     }
 
-    #[test]
-    fn test_compare_dices_user_wins() {
-        assert_eq!(DiceGame::compare_dices(2, 4), "🎉 Пользователь победил!");
+    /// Оптимальная по Келли доля банка для ставки
+    ///
+    /// `multiplier` — полная выплата на единицу ставки (включая ставку).
+    /// При неположительном преимуществе и для NaN возвращает 0.0.
+    pub fn kelly_fraction(win_prob: f64, multiplier: f64) -> f64 {
+        if multiplier <= 1.0 || multiplier.is_nan() || win_prob.is_nan() {
+            return 0.0;
+        }
+        let fraction = (win_prob * multiplier - 1.0) / (multiplier - 1.0);
+        fraction.clamp(0.0, 1.0)
     }
 
-    #[test]
-    fn test_compare_dices_tie() {
-        assert_eq!(DiceGame::compare_dices(3, 3), "🤝 Ничья!");
+    /// Ожидаемая длина самой длинной серии проигрышей за сессию
+    ///
+    /// Грубое приближение `log_{1/q}(rounds)`, где `q` — вероятность
+    /// проигрыша. Для игр около 50/50 отличается от точного значения
+    /// примерно на один раунд, при высокой вероятности проигрыша
+    /// завышает оценку. При `q <= 0` возвращает 0.0, при `q >= 1` — `rounds`.
+    pub fn expected_longest_losing_streak(lose_prob: f64, rounds: u64) -> f64 {
+        if lose_prob <= 0.0 || rounds == 0 {
+            return 0.0;
+        }
+        if lose_prob >= 1.0 {
+            return rounds as f64;
+        }
+        (rounds as f64).ln() / (1.0 / lose_prob).ln()
     }
 
-    mod properties {
-        use super::*;
-        use proptest::prelude::*;
+    /// Множитель выплаты с учетом заявленной уверенности игрока
+    ///
+    /// Уверенность 0.5 оставляет множитель без изменений, 0.0 уменьшает его
+    /// вдвое, 1.0 увеличивает в полтора раза. Ставка в слое ставок растет
+    /// пропорционально уверенности. Возвращает 0.0, если уверенность вне
+    /// диапазона `0.0..=1.0` или множитель равен NaN.
+    pub fn confidence_adjusted_payout(base_multiplier: f64, confidence: f64) -> f64 {
+        if !(0.0..=1.0).contains(&confidence) || base_multiplier.is_nan() {
+            return 0.0;
+        }
+        base_multiplier * (0.5 + confidence)
+    }
 
-        proptest! {
-            #[test]
-            fn even_odd_property(dice_result in 1u8..=6u8) {
-                let is_even = dice_result % 2 == 0;
-                prop_assert_eq!(DiceGame::check_even_odd(dice_result, EvenOddChoice::Even), is_even);
-                prop_assert_eq!(DiceGame::check_even_odd(dice_result, EvenOddChoice::Odd), !is_even);
+    /// Возврат части проигрыша после длинной серии поражений
+    ///
+    /// Возвращает `fraction` от `total_lost` (с округлением вниз), когда
+    /// серия достигла `threshold`, иначе 0. Доля ограничена `0.0..=1.0`,
+    /// поэтому возврат никогда не превышает проигранной суммы.
+    pub fn streak_refund(
+        consecutive_losses: u32,
+        total_lost: u64,
+        threshold: u32,
+        fraction: f64,
+    ) -> u64 {
+        if consecutive_losses < threshold || fraction.is_nan() {
+            return 0;
+        }
+        let refund = (total_lost as f64 * fraction.clamp(0.0, 1.0)).floor() as u64;
+        refund.min(total_lost)
+    }
+
+    /// Чистый результат раунда для баланса игрока
+    ///
+    /// При выигрыше это прибыль сверх ставки, `bet * (multiplier - 1)`,
+    /// округленная вниз, как и остальные выплаты; при проигрыше — минус
+    /// ставка. Суммы вне диапазона `i64` насыщаются.
+    pub fn round_net(won: bool, bet: u64, multiplier: f64) -> i64 {
+        if won {
+            (bet as f64 * (multiplier - 1.0)).floor() as i64
+        } else {
+            -(bet.min(i64::MAX as u64) as i64)
+        }
+    }
+
+    /// Вероятность серии из `length` выигрышей подряд
+    ///
+    /// Предполагает, что раунды независимы и вероятность выигрыша
+    /// в каждом из них одинакова.
+    pub fn streak_probability(win_prob: f64, length: u32) -> f64 {
+        win_prob.powi(length.min(i32::MAX as u32) as i32)
+    }
+
+    /// Рекомендуемое число удвоений подряд в "удвоить или потерять"
+    ///
+    /// Модель: у игрока есть запас 1 вне игры и ставка 1; после `n` удвоений
+    /// подряд он забирает `2^n` с вероятностью `win_prob^n`, иначе остается
+    /// с запасом. Полезность богатства — CRRA с коэффициентом неприятия
+    /// риска `utility_aversion` (0 — нейтральность к риску, 1 — логарифм).
+    /// Раунды независимы, выплата ровно 2x. Выбирается `n` с наибольшей
+    /// ожидаемой полезностью, при равенстве — меньшее, но не больше
+    /// `MAX_RECOMMENDED_DOUBLINGS`.
+    pub fn optimal_stop_round(win_prob: f64, utility_aversion: f64) -> u32 {
+        if win_prob.is_nan() || utility_aversion.is_nan() {
+            return 0;
+        }
+        let win_prob = win_prob.clamp(0.0, 1.0);
+        let aversion = utility_aversion.max(0.0);
+        let utility = |wealth: f64| {
+            if (aversion - 1.0).abs() < 1e-12 {
+                wealth.ln()
+            } else {
+                wealth.powf(1.0 - aversion) / (1.0 - aversion)
             }
+        };
 
-            #[test]
-            fn high_low_property(dice_result in 1u8..=6u8) {
-                let is_high = dice_result >= 4;
-                prop_assert_eq!(DiceGame::check_high_low(dice_result, HighLowChoice::High), is_high);
-                prop_assert_eq!(DiceGame::check_high_low(dice_result, HighLowChoice::Low), !is_high);
+        let mut best = (0, f64::NEG_INFINITY);
+        for doublings in 0..=MAX_RECOMMENDED_DOUBLINGS {
+            let survive = win_prob.powi(doublings as i32);
+            let expected = survive * utility(1.0 + 2f64.powi(doublings as i32))
+                + (1.0 - survive) * utility(1.0);
+            if expected > best.1 + 1e-12 {
+                best = (doublings, expected);
             }
+        }
+        best.0
+    }
 
-            #[test]
-            fn exact_number_property(dice_result in 1u8..=6u8) {
-                prop_assert!(DiceGame::check_exact_number(dice_result, dice_result));
-                let other = if dice_result == 6 { 1 } else { dice_result + 1 };
-                prop_assert!(!DiceGame::check_exact_number(dice_result, other));
+    /// Вероятность выиграть хотя бы раз за `tries` попыток
+    ///
+    /// Равна `1 - (1 - p)^tries` для независимых раундов; без попыток — 0.0.
+    pub fn prob_at_least_one_win(win_prob: f64, tries: u32) -> f64 {
+        if tries == 0 {
+            return 0.0;
+        }
+        1.0 - (1.0 - win_prob.clamp(0.0, 1.0)).powi(tries.min(i32::MAX as u32) as i32)
+    }
+
+    /// Вероятность разориться до достижения цели (задача о разорении игрока)
+    ///
+    /// Ставка — одна единица за раунд с выплатой 2x. Для честной игры
+    /// вероятность равна `1 - bankroll / target`, иначе
+    /// `(r^bankroll - r^target) / (1 - r^target)`, где `r = q / p`. При
+    /// `r > 1` формула считается через отрицательные степени, чтобы не
+    /// переполниться на больших целях.
+    pub fn probability_of_ruin(win_prob: f64, bankroll_units: u32, target_units: u32) -> f64 {
+        if bankroll_units >= target_units {
+            return 0.0;
+        }
+        if bankroll_units == 0 || win_prob.is_nan() || win_prob <= 0.0 {
+            return 1.0;
+        }
+        if win_prob >= 1.0 {
+            return 0.0;
+        }
+
+        let bankroll = bankroll_units as f64;
+        let target = target_units as f64;
+        if (win_prob - 0.5).abs() < 1e-12 {
+            return 1.0 - bankroll / target;
+        }
+        let ratio = (1.0 - win_prob) / win_prob;
+        if ratio > 1.0 {
+            (ratio.powf(bankroll - target) - 1.0) / (ratio.powf(-target) - 1.0)
+        } else {
+            (ratio.powf(bankroll) - ratio.powf(target)) / (1.0 - ratio.powf(target))
+        }
+    }
+
+    /// Собственная информация исхода в битах (для значка "редкость")
+    ///
+    /// Равна `-log2` вероятности случившегося исхода: выигрыша при `won`,
+    /// проигрыша иначе. Неизбежный исход дает 0 бит, невозможный —
+    /// бесконечность.
+    pub fn surprise_bits(win_prob: f64, won: bool) -> f64 {
+        let win_prob = win_prob.clamp(0.0, 1.0);
+        let probability = if won { win_prob } else { 1.0 - win_prob };
+        (-probability.log2()).max(0.0)
+    }
+
+    /// Средняя длина сессии до разорения по методу Монте-Карло
+    ///
+    /// Каждая из `trials` симуляций ставит `bet` за раунд, пока баланс
+    /// позволяет ставку; выигрыш начисляется как в `round_net`. Игра с
+    /// положительным ожиданием может не разориться вовсе, поэтому симуляция
+    /// обрывается на `MAX_SIMULATED_ROUNDS` раундах и результат — оценка
+    /// снизу. Без симуляций возвращает 0.0.
+    pub fn expected_rounds_until_broke(
+        win_prob: f64,
+        multiplier: f64,
+        bankroll: u64,
+        bet: u64,
+        rng: &mut impl Rng,
+        trials: u32,
+    ) -> f64 {
+        if trials == 0 {
+            return 0.0;
+        }
+        let win_prob = if win_prob.is_nan() {
+            0.0
+        } else {
+            win_prob.clamp(0.0, 1.0)
+        };
+
+        let mut total_rounds = 0u64;
+        for _ in 0..trials {
+            let mut balance = bankroll;
+            let mut rounds = 0;
+            while balance >= bet && rounds < MAX_SIMULATED_ROUNDS {
+                rounds += 1;
+                let net = Self::round_net(rng.gen_bool(win_prob), bet, multiplier);
+                balance = balance.saturating_add_signed(net);
+            }
+            total_rounds += rounds as u64;
+        }
+        total_rounds as f64 / trials as f64
+    }
+
+    /// Уровень игрока по проценту побед
+    ///
+    /// До `SKILL_TIER_MIN_GAMES` игр всегда `Novice`, чтобы несколько
+    /// удачных бросков не давали высокий уровень. Дальше: от 45% —
+    /// `Skilled`, от 60% — `Expert`, от 75% — `Lucky`.
+    pub fn skill_tier(win_rate: f64, games_played: u64) -> SkillTier {
+        if games_played < SKILL_TIER_MIN_GAMES || win_rate.is_nan() {
+            return SkillTier::Novice;
+        }
+        if win_rate >= 0.75 {
+            SkillTier::Lucky
+        } else if win_rate >= 0.6 {
+            SkillTier::Expert
+        } else if win_rate >= 0.45 {
+            SkillTier::Skilled
+        } else {
+            SkillTier::Novice
+        }
+    }
+
+    /// Медиана бросков сессии
+    ///
+    /// Для четного числа бросков — среднее двух средних значений.
+    /// Для пустой сессии возвращает `None`.
+    pub fn median_roll(rolls: &[u8]) -> Option<f64> {
+        if rolls.is_empty() {
+            return None;
+        }
+        let mut sorted = rolls.to_vec();
+        sorted.sort_unstable();
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            Some((sorted[mid - 1] as f64 + sorted[mid] as f64) / 2.0)
+        } else {
+            Some(sorted[mid] as f64)
+        }
+    }
+}
+
+/// Набор сообщений о результате на выбранном языке
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct MessagePool {
+    pub lang: Language,
+}
+
+impl MessagePool {
+    /// Сообщение о выигрыше в персональном для игрока порядке
+    ///
+    /// Работает как `DiceGame::win_message_for`, но на языке пула.
+    pub fn random_win_for(&self, player: PlayerId, nonce: u64) -> &'static str {
+        DiceGame::personal_message(true, self.lang, player, nonce)
+    }
+}
+
+/// Защита от повторного зачисления выигрыша за один и тот же раунд
+///
+/// Хранит не более `capacity` (минимум один) последних идентификаторов
+/// раундов, самые старые вытесняются.
+pub struct ClaimGuard {
+    capacity: usize,
+    settled: HashSet<u64>,
+    order: VecDeque<u64>,
+}
+
+impl ClaimGuard {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            settled: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Попытка зачесть раунд; `false`, если раунд уже был зачтен
+    pub fn try_settle(&mut self, round_id: u64) -> bool {
+        if !self.settled.insert(round_id) {
+            return false;
+        }
+        self.order.push_back(round_id);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.settled.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Многоуровневая отмена: последние `capacity` (минимум одно) состояний
+///
+/// При переполнении самое старое состояние вытесняется, поэтому отменить
+/// можно не больше `capacity` шагов.
+pub struct UndoStack<T> {
+    capacity: usize,
+    states: VecDeque<T>,
+}
+
+impl<T> UndoStack<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            states: VecDeque::new(),
+        }
+    }
+
+    /// Сохранение состояния перед действием
+    pub fn push(&mut self, state: T) {
+        self.states.push_back(state);
+        if self.states.len() > self.capacity {
+            self.states.pop_front();
+        }
+    }
+
+    /// Возврат последнего сохраненного состояния; `None`, если отменять нечего
+    pub fn undo(&mut self) -> Option<T> {
+        self.states.pop_back()
+    }
+}
+
+/// Одна бесплатная игра в календарный день для каждого игрока
+#[derive(Debug, Default)]
+pub struct DailyFreePlay {
+    last_played: HashMap<PlayerId, NaiveDate>,
+}
+
+impl DailyFreePlay {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Доступна ли игроку бесплатная игра сегодня
+    pub fn can_play(&self, player: PlayerId, today: NaiveDate) -> bool {
+        self.last_played
+            .get(&player)
+            .is_none_or(|&last| last < today)
+    }
+
+    /// Использование бесплатной игры; `false`, если сегодня она уже была
+    pub fn consume(&mut self, player: PlayerId, today: NaiveDate) -> bool {
+        if !self.can_play(player, today) {
+            return false;
+        }
+        self.last_played.insert(player, today);
+        true
+    }
+}
+
+/// Стратегия выбора бота-соперника
+#[derive(Clone, Debug, PartialEq)]
+pub enum BotStrategy {
+    Random,                      // Случайный допустимый выбор на d6
+    Smart { weights: Vec<f64> }, // Лучший выбор с учетом известного перекоса кубика
+}
+
+impl BotStrategy {
+    /// Выбор варианта игры ботом
+    pub fn choose(&self, mode_kind: ModeKind, rng: &mut impl Rng) -> GameMode {
+        match self {
+            BotStrategy::Random => DiceGame::mode_choices(mode_kind, 6)
+                .choose(rng)
+                .cloned()
+                // mode_choices всегда возвращает хотя бы один вариант
+                .unwrap(),
+            BotStrategy::Smart { weights } => DiceGame::optimal_choice(weights, mode_kind),
+        }
+    }
+}
+
+/// "Правило милосердия": гарантированный выигрыш после серии проигрышей
+///
+/// Нарушает честность игры, поэтому включается только явно и только для
+/// игр без денежных ставок.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MercyRule {
+    pub threshold: u32,
+    pub active_losses: u32,
+}
+
+impl MercyRule {
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            active_losses: 0,
+        }
+    }
+
+    /// Нужно ли гарантировать выигрыш в следующем раунде
+    pub fn should_grant(&self) -> bool {
+        self.threshold > 0 && self.active_losses >= self.threshold
+    }
+
+    /// Учет результата раунда: выигрыш сбрасывает счетчик проигрышей
+    pub fn register(&mut self, won: bool) {
+        if won {
+            self.active_losses = 0;
+        } else {
+            self.active_losses = self.active_losses.saturating_add(1);
+        }
+    }
+}
+
+/// "Таймер жалости": растущая прибавка к шансу угадать точное число
+///
+/// Как и `MercyRule`, нарушает честность игры: с прибавкой реальная
+/// вероятность выигрыша выше заявленной, поэтому таймер предназначен только
+/// для одиночной игры без денежных ставок. Прибавка растет на `step` за
+/// каждый проигрыш подряд, не превышает `max_boost` и сбрасывается при
+/// выигрыше.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PityTimer {
+    pub step: f64,
+    pub max_boost: f64,
+    pub active_losses: u32,
+}
+
+impl PityTimer {
+    pub fn new(step: f64, max_boost: f64) -> Self {
+        Self {
+            step: step.max(0.0),
+            max_boost: max_boost.clamp(0.0, 1.0),
+            active_losses: 0,
+        }
+    }
+
+    /// Текущая прибавка к вероятности выигрыша (нечестная)
+    pub fn current_boost(&self) -> f64 {
+        (self.step * self.active_losses as f64).min(self.max_boost)
+    }
+
+    /// Учет результата раунда: выигрыш сбрасывает прибавку
+    pub fn register(&mut self, won: bool) {
+        if won {
+            self.active_losses = 0;
+        } else {
+            self.active_losses = self.active_losses.saturating_add(1);
+        }
+    }
+}
+
+/// Срабатывание бонусного раунда
+#[derive(Clone, Debug, PartialEq)]
+pub struct BonusTrigger {
+    pub face: u8,      // Повторившееся значение
+    pub length: usize, // Длина серии
+}
+
+/// Детектор серий одинаковых бросков для запуска бонусного раунда
+///
+/// После срабатывания серия начинается заново, поэтому длинная серия
+/// дает бонус раз в `repeat` бросков.
+pub struct PatternDetector {
+    repeat: usize,
+    last: Option<u8>,
+    run: usize,
+}
+
+impl PatternDetector {
+    pub fn new(repeat: usize) -> Self {
+        Self {
+            repeat: repeat.max(1),
+            last: None,
+            run: 0,
+        }
+    }
+
+    /// Учет очередного броска
+    pub fn feed(&mut self, roll: u8) -> Option<BonusTrigger> {
+        if self.last == Some(roll) {
+            self.run += 1;
+        } else {
+            self.last = Some(roll);
+            self.run = 1;
+        }
+
+        if self.run < self.repeat {
+            return None;
+        }
+        self.last = None;
+        self.run = 0;
+        Some(BonusTrigger {
+            face: roll,
+            length: self.repeat,
+        })
+    }
+}
+
+/// Множитель комбо за повтор одинаковых бросков подряд
+///
+/// Начинается с 1.0, растет на `step` за каждый повтор предыдущего
+/// значения и сбрасывается при другом броске. Ограничен сверху `cap`.
+pub struct ComboMultiplier {
+    step: f64,
+    cap: f64,
+    last: Option<u8>,
+    current: f64,
+}
+
+impl ComboMultiplier {
+    pub fn new(step: f64, cap: f64) -> Self {
+        Self {
+            step: step.max(0.0),
+            cap: cap.max(1.0),
+            last: None,
+            current: 1.0,
+        }
+    }
+
+    /// Учет броска и текущий множитель
+    pub fn feed(&mut self, roll: u8) -> f64 {
+        if self.last == Some(roll) {
+            self.current = (self.current + self.step).min(self.cap);
+        } else {
+            self.last = Some(roll);
+            self.current = 1.0;
+        }
+        self.current
+    }
+}
+
+/// Ошибка разделения ставки
+#[derive(Clone, Debug, PartialEq)]
+pub enum BetError {
+    AllocationMismatch { total: u64, allocated: u128 }, // Доли не дают в сумме ставку
+    InvalidMode(ModeError),                             // Режим недопустим для кубика
+    NoSides,                                            // Кубик без граней нельзя бросить
+}
+
+/// Расчет одной доли разделенной ставки
+#[derive(Clone, Debug, PartialEq)]
+pub struct BetSettlement {
+    pub result: GameResult, // Режим, бросок и исход
+    pub stake: u64,         // Доля ставки
+    pub payout: u64,        // Выплата (0 при проигрыше)
+}
+
+/// Строка таблицы шансов `DiceGame::odds_table`
+#[derive(Clone, Debug, PartialEq)]
+pub struct OddsRow {
+    pub mode: GameMode,
+    pub name: &'static str,
+    pub win_probability: f64,
+    pub fair_multiplier: Option<f64>, // None, если режим не может выиграть
+}
+
+/// Жетоны переброса неудачного результата
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct RerollTokens {
+    pub count: u32,
+}
+
+impl RerollTokens {
+    /// Списание одного жетона; `false`, если жетонов не осталось
+    pub fn spend(&mut self) -> bool {
+        if self.count == 0 {
+            return false;
+        }
+        self.count -= 1;
+        true
+    }
+
+    /// Переброс кубика с `sides` гранями за один жетон; `None`, если
+    /// жетонов не осталось
+    pub fn reroll(&mut self, sides: u8, rng: &mut impl Rng) -> Option<u8> {
+        if !self.spend() {
+            return None;
+        }
+        Some(rng.gen_range(1..=sides.max(1)))
+    }
+}
+
+/// Фильтрация сессии до выигранных раундов (для подборки лучших моментов)
+pub trait WinningRoundsExt: Iterator<Item = GameResult> + Sized {
+    fn winning(self) -> std::iter::Filter<Self, fn(&GameResult) -> bool> {
+        self.filter(|result| result.won)
+    }
+}
+
+impl<I: Iterator<Item = GameResult>> WinningRoundsExt for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{
+        EvenOddChoice, GameFsm, GameMode, GameResult, GuessOneChoice, HighLowChoice, Language,
+        ModeKind, SessionLog, SkillTier, SumMode, Theme,
+    };
+    use pretty_assertions::assert_eq;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_check_even_odd_basic() {
+        assert!(DiceGame::check_even_odd(2, EvenOddChoice::Even));
+        assert!(!DiceGame::check_even_odd(2, EvenOddChoice::Odd));
+        assert!(DiceGame::check_even_odd(5, EvenOddChoice::Odd));
+        assert!(!DiceGame::check_even_odd(5, EvenOddChoice::Even));
+    }
+
+    #[test]
+    fn test_check_high_low_basic() {
+        assert!(DiceGame::check_high_low(1, HighLowChoice::Low));
+        assert!(DiceGame::check_high_low(3, HighLowChoice::Low));
+        assert!(!DiceGame::check_high_low(3, HighLowChoice::High));
+        assert!(DiceGame::check_high_low(4, HighLowChoice::High));
+        assert!(DiceGame::check_high_low(6, HighLowChoice::High));
+        assert!(!DiceGame::check_high_low(4, HighLowChoice::Low));
+    }
+
+    #[test]
+    fn test_check_exact_number_basic() {
+        assert!(DiceGame::check_exact_number(4, 4));
+        assert!(!DiceGame::check_exact_number(1, 6));
+    }
+
+    #[test]
+    fn test_check_within_window_hit() {
+        assert!(DiceGame::check_within(3, 4, 1));
+        assert!(DiceGame::check_within(5, 4, 1));
+        assert!(DiceGame::check_within(6, 4, 2));
+    }
+
+    #[test]
+    fn test_check_within_zero_tolerance() {
+        assert!(DiceGame::check_within(4, 4, 0));
+        assert!(!DiceGame::check_within(3, 4, 0));
+    }
+
+    #[test]
+    fn test_check_within_miss() {
+        assert!(!DiceGame::check_within(1, 4, 2));
+        assert!(!DiceGame::check_within(6, 1, 4));
+    }
+
+    #[test]
+    fn test_near_miss_score_exact() {
+        assert_eq!(DiceGame::near_miss_score(4, 4, 6), 1.0);
+    }
+
+    #[test]
+    fn test_near_miss_score_one_off() {
+        let score = DiceGame::near_miss_score(3, 4, 6);
+        assert!((score - 0.8).abs() < 1e-9);
+        assert!(score > 0.0 && score < 1.0);
+    }
+
+    #[test]
+    fn test_near_miss_score_farthest() {
+        assert_eq!(DiceGame::near_miss_score(1, 6, 6), 0.0);
+        assert_eq!(DiceGame::near_miss_score(6, 1, 6), 0.0);
+    }
+
+    #[test]
+    fn test_margin_payout_minimal_margin() {
+        let payout = DiceGame::margin_payout(4, 3, true, 3.0, 6);
+        assert!((payout - 1.0).abs() < 1e-9);
+        let payout = DiceGame::margin_payout(3, 3, false, 3.0, 6);
+        assert!((payout - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_margin_payout_maximal_margin() {
+        assert!((DiceGame::margin_payout(6, 3, true, 3.0, 6) - 3.0).abs() < 1e-9);
+        assert!((DiceGame::margin_payout(1, 3, false, 3.0, 6) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_margin_payout_loss() {
+        assert_eq!(DiceGame::margin_payout(3, 3, true, 3.0, 6), 0.0);
+        assert_eq!(DiceGame::margin_payout(5, 3, false, 3.0, 6), 0.0);
+    }
+
+    #[test]
+    fn test_check_combined_both_correct() {
+        assert!(DiceGame::check_combined(
+            6,
+            EvenOddChoice::Even,
+            HighLowChoice::High
+        ));
+        assert!(DiceGame::check_combined(
+            1,
+            EvenOddChoice::Odd,
+            HighLowChoice::Low
+        ));
+    }
+
+    #[test]
+    fn test_check_combined_parity_wrong() {
+        assert!(!DiceGame::check_combined(
+            5,
+            EvenOddChoice::Even,
+            HighLowChoice::High
+        ));
+    }
+
+    #[test]
+    fn test_check_combined_range_wrong() {
+        assert!(!DiceGame::check_combined(
+            2,
+            EvenOddChoice::Even,
+            HighLowChoice::High
+        ));
+    }
+
+    #[test]
+    fn test_check_combined_both_wrong() {
+        assert!(!DiceGame::check_combined(
+            3,
+            EvenOddChoice::Even,
+            HighLowChoice::High
+        ));
+    }
+
+    #[test]
+    fn test_check_guess_one_basic() {
+        assert!(DiceGame::check_guess_one(1, GuessOneChoice::Yes));
+        assert!(!DiceGame::check_guess_one(1, GuessOneChoice::No));
+        assert!(DiceGame::check_guess_one(3, GuessOneChoice::No));
+        assert!(!DiceGame::check_guess_one(3, GuessOneChoice::Yes));
+    }
+
+    #[test]
+    fn test_check_sum_parity_basic() {
+        assert!(DiceGame::check_sum_parity(1, 3, EvenOddChoice::Even));
+        assert!(DiceGame::check_sum_parity(2, 5, EvenOddChoice::Odd));
+        assert!(!DiceGame::check_sum_parity(6, 6, EvenOddChoice::Odd));
+    }
+
+    #[test]
+    fn test_check_multiset_exact_match() {
+        assert!(DiceGame::check_multiset(&[2, 4, 4], &[2, 4, 4]));
+        assert!(!DiceGame::check_multiset(&[2, 4, 4], &[2, 2, 4]));
+    }
+
+    #[test]
+    fn test_check_multiset_permutation() {
+        assert!(DiceGame::check_multiset(&[6, 1, 3], &[3, 6, 1]));
+    }
+
+    #[test]
+    fn test_check_multiset_length_mismatch() {
+        assert!(!DiceGame::check_multiset(&[1, 2, 3], &[1, 2]));
+        assert!(!DiceGame::check_multiset(&[], &[1]));
+    }
+
+    #[test]
+    fn test_winning_faces_even_odd() {
+        assert_eq!(
+            DiceGame::winning_faces(&GameMode::EvenOdd(EvenOddChoice::Even), 6),
+            vec![2, 4, 6]
+        );
+        assert_eq!(
+            DiceGame::winning_faces(&GameMode::EvenOdd(EvenOddChoice::Odd), 6),
+            vec![1, 3, 5]
+        );
+    }
+
+    #[test]
+    fn test_winning_faces_high_low() {
+        assert_eq!(
+            DiceGame::winning_faces(&GameMode::HighLow(HighLowChoice::High), 6),
+            vec![4, 5, 6]
+        );
+        assert_eq!(
+            DiceGame::winning_faces(&GameMode::HighLow(HighLowChoice::Low), 6),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_winning_faces_exact() {
+        assert_eq!(DiceGame::winning_faces(&GameMode::Exact(5), 6), vec![5]);
+        assert!(DiceGame::winning_faces(&GameMode::Exact(7), 6).is_empty());
+    }
+
+    #[test]
+    fn test_outcome_message_win_pool() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            assert!(WIN_MESSAGES_RU.contains(&DiceGame::outcome_message(
+                true,
+                Language::Ru,
+                &mut rng
+            )));
+            assert!(WIN_MESSAGES_EN.contains(&DiceGame::outcome_message(
+                true,
+                Language::En,
+                &mut rng
+            )));
+        }
+    }
+
+    #[test]
+    fn test_outcome_message_lose_pool() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            assert!(LOSE_MESSAGES_RU.contains(&DiceGame::outcome_message(
+                false,
+                Language::Ru,
+                &mut rng
+            )));
+            assert!(LOSE_MESSAGES_EN.contains(&DiceGame::outcome_message(
+                false,
+                Language::En,
+                &mut rng
+            )));
+        }
+    }
+
+    #[test]
+    fn test_win_probability_basic() {
+        let even = GameMode::EvenOdd(EvenOddChoice::Even);
+        assert!((DiceGame::win_probability(&even, 6) - 0.5).abs() < 1e-9);
+        assert!((DiceGame::win_probability(&GameMode::Exact(3), 6) - 1.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_outcome_distribution_even_odd_sums_to_one() {
+        let distribution =
+            DiceGame::outcome_distribution(&GameMode::EvenOdd(EvenOddChoice::Even), 6);
+        assert_eq!(distribution.len(), 2);
+        assert!(distribution[0].0);
+        assert!((distribution[0].1 - 0.5).abs() < 1e-9);
+        let total: f64 = distribution.iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_outcome_distribution_exact_sums_to_one() {
+        let distribution = DiceGame::outcome_distribution(&GameMode::Exact(2), 6);
+        let total: f64 = distribution.iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!((distribution[1].1 - 5.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_excitement_score_exact_above_even_odd() {
+        let exact = DiceGame::excitement_score(&GameMode::Exact(4), 6);
+        let even_odd = DiceGame::excitement_score(&GameMode::EvenOdd(EvenOddChoice::Odd), 6);
+        assert!(exact > even_odd);
+        assert!((exact - 5.0).abs() < 1e-9);
+        assert!((even_odd - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_payout_variance_impossible_mode() {
+        assert_eq!(DiceGame::payout_variance(&GameMode::Exact(9), 6), 0.0);
+    }
+
+    #[test]
+    fn test_fair_multiplier_impossible_mode() {
+        assert_eq!(DiceGame::fair_multiplier(&GameMode::Exact(7), 6), None);
+    }
+
+    #[test]
+    fn test_optimal_choice_even_loaded_die() {
+        let weights = [0.1, 0.25, 0.1, 0.2, 0.1, 0.25];
+        assert_eq!(
+            DiceGame::optimal_choice(&weights, ModeKind::EvenOdd),
+            GameMode::EvenOdd(EvenOddChoice::Even)
+        );
+    }
+
+    #[test]
+    fn test_optimal_choice_exact_and_high_low() {
+        let weights = [0.6, 0.08, 0.08, 0.08, 0.08, 0.08];
+        assert_eq!(
+            DiceGame::optimal_choice(&weights, ModeKind::Exact),
+            GameMode::Exact(1)
+        );
+        assert_eq!(
+            DiceGame::optimal_choice(&weights, ModeKind::HighLow),
+            GameMode::HighLow(HighLowChoice::Low)
+        );
+        assert_eq!(
+            DiceGame::optimal_choice(&weights, ModeKind::GuessOne),
+            GameMode::GuessOne(GuessOneChoice::Yes)
+        );
+    }
+
+    #[test]
+    fn test_format_currency_ru() {
+        assert_eq!(DiceGame::format_currency(0, Language::Ru), "0 ₽");
+        assert_eq!(DiceGame::format_currency(999, Language::Ru), "999 ₽");
+        assert_eq!(DiceGame::format_currency(1000, Language::Ru), "1 000 ₽");
+        assert_eq!(
+            DiceGame::format_currency(1234567, Language::Ru),
+            "1 234 567 ₽"
+        );
+    }
+
+    #[test]
+    fn test_format_currency_en() {
+        assert_eq!(DiceGame::format_currency(5, Language::En), "$5");
+        assert_eq!(DiceGame::format_currency(1000, Language::En), "$1,000");
+        assert_eq!(
+            DiceGame::format_currency(1234567, Language::En),
+            "$1,234,567"
+        );
+    }
+
+    #[test]
+    fn test_frame_delays_sum_to_total() {
+        let delays = DiceGame::frame_delays(6, 3000);
+        assert_eq!(delays.len(), 6);
+        assert_eq!(delays.iter().sum::<u64>(), 3000);
+    }
+
+    #[test]
+    fn test_frame_delays_increasing() {
+        let delays = DiceGame::frame_delays(8, 3000);
+        assert!(delays.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_frame_delays_small_total() {
+        let delays = DiceGame::frame_delays(3, 6);
+        assert_eq!(delays, vec![1, 2, 3]);
+        let delays = DiceGame::frame_delays(4, 11);
+        assert_eq!(delays.iter().sum::<u64>(), 11);
+        assert!(delays.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_frame_delays_budget_below_ramp() {
+        let delays = DiceGame::frame_delays(6, 10);
+        assert_eq!(delays.iter().sum::<u64>(), 10);
+        assert!(delays.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(DiceGame::frame_delays(3, 1), vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn test_frame_delays_empty() {
+        assert!(DiceGame::frame_delays(0, 3000).is_empty());
+    }
+
+    #[test]
+    fn test_replay_reproduces_session() {
+        let modes = vec![
+            GameMode::EvenOdd(EvenOddChoice::Even),
+            GameMode::Exact(3),
+            GameMode::HighLow(HighLowChoice::Low),
+            GameMode::GuessOne(GuessOneChoice::No),
+            GameMode::Exact(6),
+        ];
+        let mut rng = DiceGame::session_rng(56);
+        let recorded: Vec<GameResult> = modes
+            .iter()
+            .map(|mode| DiceGame::play_round(mode, 6, &mut rng))
+            .collect();
+
+        let log = SessionLog {
+            seed: 56,
+            sides: 6,
+            modes,
+        };
+        let serialized = serde_json::to_string(&log).unwrap();
+        let restored: SessionLog = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(restored, log);
+        assert_eq!(DiceGame::replay(&restored), recorded);
+    }
+
+    #[test]
+    fn test_mode_name_non_empty() {
+        for kind in ModeKind::all() {
+            for lang in [Language::Ru, Language::En] {
+                assert!(!DiceGame::mode_name(kind, lang).is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_mode_name_even_odd() {
+        assert_eq!(
+            DiceGame::mode_name(ModeKind::EvenOdd, Language::Ru),
+            "Четное/Нечетное"
+        );
+        assert_eq!(
+            DiceGame::mode_name(ModeKind::EvenOdd, Language::En),
+            "Even/Odd"
+        );
+    }
+
+    #[test]
+    fn test_share_card_win() {
+        let result = GameResult {
+            mode: GameMode::Exact(4),
+            roll: 4,
+            won: true,
+        };
+        let card = DiceGame::share_card(&result, "Vasya", Language::Ru);
+        assert_eq!(
+            card,
+            "🎲 Игрок Vasya · Точное число · выпало 4 — выиграл ×6!"
+        );
+    }
+
+    #[test]
+    fn test_share_card_loss() {
+        let result = GameResult {
+            mode: GameMode::EvenOdd(EvenOddChoice::Even),
+            roll: 3,
+            won: false,
+        };
+        let card = DiceGame::share_card(&result, "Alice", Language::En);
+        assert!(card.contains("Alice"));
+        assert!(card.contains('3'));
+        assert!(card.contains("lost"));
+    }
+
+    #[test]
+    fn test_format_probability_ru() {
+        assert_eq!(
+            DiceGame::format_probability(1.0 / 6.0, Language::Ru),
+            "16,7 %"
+        );
+        assert_eq!(DiceGame::format_probability(0.5, Language::Ru), "50,0 %");
+    }
+
+    #[test]
+    fn test_format_probability_en() {
+        assert_eq!(
+            DiceGame::format_probability(1.0 / 6.0, Language::En),
+            "16.7%"
+        );
+        assert_eq!(DiceGame::format_probability(1.0, Language::En), "100.0%");
+    }
+
+    #[test]
+    fn test_format_probability_clamped() {
+        assert_eq!(DiceGame::format_probability(1.5, Language::En), "100.0%");
+        assert_eq!(DiceGame::format_probability(-0.2, Language::Ru), "0,0 %");
+        assert_eq!(DiceGame::format_probability(f64::NAN, Language::En), "0.0%");
+    }
+
+    #[test]
+    fn test_rules_mention_win_condition() {
+        for kind in ModeKind::all() {
+            let ru = DiceGame::rules(kind, Language::Ru);
+            let en = DiceGame::rules(kind, Language::En);
+            assert!(ru.contains("выигрываете"), "{}", ru);
+            assert!(en.contains("You win"), "{}", en);
+        }
+    }
+
+    #[test]
+    fn test_rules_payouts() {
+        assert!(DiceGame::rules(ModeKind::Exact, Language::En).contains("×6"));
+        assert!(DiceGame::rules(ModeKind::EvenOdd, Language::Ru).contains("×2"));
+        let guess_one = DiceGame::rules(ModeKind::GuessOne, Language::Ru);
+        assert!(guess_one.contains("×6") && guess_one.contains("×1.2"));
+    }
+
+    #[test]
+    fn test_duel_outcome() {
+        assert_eq!(DiceGame::duel_outcome(5, 3), Ordering::Greater);
+        assert_eq!(DiceGame::duel_outcome(2, 4), Ordering::Less);
+        assert_eq!(DiceGame::duel_outcome(3, 3), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_dices_bot_wins() {
+        assert_eq!(DiceGame::compare_dices(5, 3), "🤖 Компьютер победил!");
+    }
+
+    #[test]
+    fn test_compare_dices_user_wins() {
+        assert_eq!(DiceGame::compare_dices(2, 4), "🎉 Пользователь победил!");
+    }
+
+    #[test]
+    fn test_compare_dices_tie() {
+        assert_eq!(DiceGame::compare_dices(3, 3), "🤝 Ничья!");
+    }
+
+    #[test]
+    fn test_compare_dices_themed_emoji() {
+        assert_eq!(
+            DiceGame::compare_dices_themed(5, 3, Theme::Emoji),
+            "🤖 Компьютер победил!"
+        );
+        assert_eq!(
+            DiceGame::compare_dices_themed(2, 4, Theme::Emoji),
+            "🎉 Пользователь победил!"
+        );
+        assert_eq!(
+            DiceGame::compare_dices_themed(3, 3, Theme::Emoji),
+            "🤝 Ничья!"
+        );
+    }
+
+    #[test]
+    fn test_compare_dices_themed_plain_text() {
+        assert_eq!(
+            DiceGame::compare_dices_themed(5, 3, Theme::PlainText),
+            "Computer won"
+        );
+        assert_eq!(
+            DiceGame::compare_dices_themed(2, 4, Theme::PlainText),
+            "User won"
+        );
+        assert_eq!(
+            DiceGame::compare_dices_themed(3, 3, Theme::PlainText),
+            "Draw"
+        );
+    }
+
+    #[test]
+    fn test_challenge_house_player_wins() {
+        assert_eq!(
+            DiceGame::challenge_house(6, &[2, 5, 3]),
+            "🎉 Пользователь победил!"
+        );
+    }
+
+    #[test]
+    fn test_challenge_house_player_loses() {
+        assert_eq!(
+            DiceGame::challenge_house(3, &[1, 4, 2]),
+            "🤖 Компьютер победил!"
+        );
+    }
+
+    #[test]
+    fn test_challenge_house_tie_goes_to_player() {
+        assert_eq!(
+            DiceGame::challenge_house(5, &[5, 1, 5]),
+            "🎉 Пользователь победил!"
+        );
+    }
+
+    #[test]
+    fn test_compare_many_single_winner() {
+        assert_eq!(DiceGame::compare_many(&[(1, 3), (2, 6), (3, 5)]), vec![2]);
+    }
+
+    #[test]
+    fn test_compare_many_tie_for_first() {
+        assert_eq!(
+            DiceGame::compare_many(&[(1, 6), (2, 2), (3, 6)]),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn test_compare_many_empty() {
+        assert!(DiceGame::compare_many(&[]).is_empty());
+    }
+
+    /// Генератор, всегда выдающий одно и то же значение и считающий вызовы
+    struct FixedRoller {
+        calls: usize,
+    }
+
+    impl rand::RngCore for FixedRoller {
+        fn next_u32(&mut self) -> u32 {
+            self.calls += 1;
+            0
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.next_u32() as u64
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.fill(0);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_compare_dices_reroll_bounded_always_tie() {
+        let mut roller = FixedRoller { calls: 0 };
+        assert_eq!(
+            DiceGame::compare_dices_reroll_bounded(3, 3, 6, 5, &mut roller),
+            "🤝 Ничья!"
+        );
+        assert_eq!(roller.calls, 10);
+    }
+
+    #[test]
+    fn test_compare_dices_reroll_bounded_no_tie() {
+        let mut roller = FixedRoller { calls: 0 };
+        assert_eq!(
+            DiceGame::compare_dices_reroll_bounded(5, 3, 6, 5, &mut roller),
+            "🤖 Компьютер победил!"
+        );
+        assert_eq!(roller.calls, 0);
+    }
+
+    #[test]
+    fn test_compare_dices_reroll_bounded_resolves_tie() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_ne!(
+            DiceGame::compare_dices_reroll_bounded(4, 4, 6, 20, &mut rng),
+            "🤝 Ничья!"
+        );
+    }
+
+    #[test]
+    fn test_implied_multiplier_half_rate() {
+        let multiplier = DiceGame::implied_multiplier(50, 100).unwrap();
+        assert!((multiplier - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_implied_multiplier_no_wins() {
+        assert_eq!(DiceGame::implied_multiplier(0, 100), None);
+    }
+
+    #[test]
+    fn test_win_rate_ci_narrows_with_sample_size() {
+        let (small_low, small_high) = DiceGame::win_rate_ci(5, 10, 1.96);
+        let (large_low, large_high) = DiceGame::win_rate_ci(500, 1000, 1.96);
+        assert!(large_high - large_low < small_high - small_low);
+        assert!(small_low < 0.5 && small_high > 0.5);
+        assert!(large_low < 0.5 && large_high > 0.5);
+    }
+
+    #[test]
+    fn test_win_rate_ci_within_bounds() {
+        for (wins, total) in [(0, 5), (5, 5), (1, 1), (3, 7)] {
+            let (low, high) = DiceGame::win_rate_ci(wins, total, 1.96);
+            assert!((0.0..=1.0).contains(&low));
+            assert!((0.0..=1.0).contains(&high));
+            assert!(low <= high);
+        }
+    }
+
+    #[test]
+    fn test_win_rate_ci_no_games() {
+        assert_eq!(DiceGame::win_rate_ci(0, 0, 1.96), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_house_edge_fair_game() {
+        assert!(DiceGame::house_edge(0.5, 2.0).abs() < 1e-9);
+        assert!(DiceGame::house_edge(1.0 / 6.0, 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_house_edge_five_percent() {
+        assert!((DiceGame::house_edge(0.5, 1.9) - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_break_even_win_rate_basic() {
+        assert!((DiceGame::break_even_win_rate(2.0) - 0.5).abs() < 1e-9);
+        assert!((DiceGame::break_even_win_rate(6.0) - 1.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_break_even_win_rate_no_profit_possible() {
+        assert_eq!(DiceGame::break_even_win_rate(1.0), 1.0);
+        assert_eq!(DiceGame::break_even_win_rate(0.5), 1.0);
+    }
+
+    #[test]
+    fn test_kelly_fraction_positive_edge() {
+        // p = 0.6 при выплате 2x: f = 2p - 1 = 0.2
+        let fraction = DiceGame::kelly_fraction(0.6, 2.0);
+        assert!((fraction - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_kelly_fraction_no_edge() {
+        assert_eq!(DiceGame::kelly_fraction(0.5, 2.0), 0.0);
+        assert_eq!(DiceGame::kelly_fraction(1.0 / 6.0, 5.0), 0.0);
+        assert_eq!(DiceGame::kelly_fraction(0.9, 1.0), 0.0);
+        assert_eq!(DiceGame::kelly_fraction(f64::NAN, 3.0), 0.0);
+        assert_eq!(DiceGame::kelly_fraction(0.5, f64::NAN), 0.0);
+    }
+
+    #[test]
+    fn test_expected_longest_losing_streak_monte_carlo() {
+        let mut rng = StdRng::seed_from_u64(44);
+        let trials = 500;
+        let rounds = 1000;
+        let mut total = 0usize;
+        for _ in 0..trials {
+            let (mut longest, mut current) = (0, 0);
+            for _ in 0..rounds {
+                if rng.gen_bool(0.5) {
+                    current += 1;
+                    longest = longest.max(current);
+                } else {
+                    current = 0;
+                }
+            }
+            total += longest;
+        }
+        let simulated = total as f64 / trials as f64;
+        let approximation = DiceGame::expected_longest_losing_streak(0.5, rounds);
+        assert!((approximation - simulated).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_expected_longest_losing_streak_edge_probabilities() {
+        assert_eq!(DiceGame::expected_longest_losing_streak(0.0, 100), 0.0);
+        assert_eq!(DiceGame::expected_longest_losing_streak(1.0, 100), 100.0);
+    }
+
+    #[test]
+    fn test_confidence_adjusted_payout_low_and_high() {
+        let low = DiceGame::confidence_adjusted_payout(2.0, 0.1);
+        let neutral = DiceGame::confidence_adjusted_payout(2.0, 0.5);
+        let high = DiceGame::confidence_adjusted_payout(2.0, 0.9);
+        assert!(low < neutral && neutral < high);
+        assert!((neutral - 2.0).abs() < 1e-9);
+        assert!((high - 2.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_confidence_adjusted_payout_invalid() {
+        assert_eq!(DiceGame::confidence_adjusted_payout(2.0, -0.1), 0.0);
+        assert_eq!(DiceGame::confidence_adjusted_payout(2.0, 1.5), 0.0);
+        assert_eq!(DiceGame::confidence_adjusted_payout(2.0, f64::NAN), 0.0);
+        assert_eq!(DiceGame::confidence_adjusted_payout(f64::NAN, 0.5), 0.0);
+    }
+
+    #[test]
+    fn test_streak_refund_below_threshold() {
+        assert_eq!(DiceGame::streak_refund(4, 500, 5, 0.2), 0);
+    }
+
+    #[test]
+    fn test_streak_refund_at_threshold() {
+        assert_eq!(DiceGame::streak_refund(5, 500, 5, 0.2), 100);
+        assert_eq!(DiceGame::streak_refund(8, 333, 5, 0.5), 166);
+    }
+
+    #[test]
+    fn test_streak_refund_capped() {
+        assert_eq!(DiceGame::streak_refund(5, 500, 5, 3.0), 500);
+        assert_eq!(DiceGame::streak_refund(5, 500, 5, -1.0), 0);
+    }
+
+    #[test]
+    fn test_conditional_win_probability_high_first_die() {
+        let over_seven = SumMode::Over(7);
+        // Без раскрытия вероятность суммы больше 7 на 2d6 равна 15/36
+        let prior: f64 = (1..=6)
+            .map(|first| DiceGame::conditional_win_probability(&over_seven, first, 6))
+            .sum::<f64>()
+            / 6.0;
+        assert!((prior - 15.0 / 36.0).abs() < 1e-12);
+
+        let after_six = DiceGame::conditional_win_probability(&over_seven, 6, 6);
+        assert!((after_six - 5.0 / 6.0).abs() < 1e-12);
+        assert!(after_six > prior);
+    }
+
+    #[test]
+    fn test_conditional_win_probability_low_first_die() {
+        assert_eq!(
+            DiceGame::conditional_win_probability(&SumMode::Over(7), 1, 6),
+            0.0
+        );
+        let exact = DiceGame::conditional_win_probability(&SumMode::Exactly(7), 1, 6);
+        assert!((exact - 1.0 / 6.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_difficulty_rating_ordering() {
+        let rating = DiceGame::difficulty_rating;
+        assert!(rating(ModeKind::EvenOdd) < rating(ModeKind::HighLow));
+        assert!(rating(ModeKind::HighLow) < rating(ModeKind::GuessOne));
+        assert!(rating(ModeKind::GuessOne) < rating(ModeKind::Exact));
+    }
+
+    #[test]
+    fn test_difficulty_rating_scale() {
+        for kind in ModeKind::all() {
+            assert!((1..=10).contains(&DiceGame::difficulty_rating(kind)));
+        }
+    }
+
+    #[test]
+    fn test_parlay_fair_multiplier_product_of_legs() {
+        let legs = [GameMode::EvenOdd(EvenOddChoice::Even), GameMode::Exact(3)];
+        let expected = DiceGame::fair_multiplier(&legs[0], 6).unwrap()
+            * DiceGame::fair_multiplier(&legs[1], 6).unwrap();
+        let parlay = DiceGame::parlay_fair_multiplier(&legs, 6);
+        assert!((parlay - expected).abs() < 1e-9);
+        assert!((parlay - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parlay_fair_multiplier_empty() {
+        assert_eq!(DiceGame::parlay_fair_multiplier(&[], 6), 1.0);
+    }
+
+    #[test]
+    fn test_parlay_multiplier_with_edge() {
+        let legs = [GameMode::EvenOdd(EvenOddChoice::Even), GameMode::Exact(3)];
+        let with_edge = DiceGame::parlay_multiplier_with_edge(&legs, 6, 0.05);
+        assert!((with_edge - 11.4).abs() < 1e-9);
+        assert_eq!(DiceGame::parlay_multiplier_with_edge(&[], 6, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_choice_entropy_even_odd() {
+        assert_eq!(DiceGame::choice_entropy(ModeKind::EvenOdd, 6), 1.0);
+    }
+
+    #[test]
+    fn test_choice_entropy_exact_d8() {
+        assert!((DiceGame::choice_entropy(ModeKind::Exact, 8) - 3.0).abs() < 1e-12);
+        assert_eq!(DiceGame::choice_entropy(ModeKind::Exact, 0), 0.0);
+    }
+
+    #[test]
+    fn test_odds_table_covers_all_kinds() {
+        let table = DiceGame::odds_table(6, Language::Ru);
+        for kind in ModeKind::all() {
+            assert!(table.iter().any(|row| row.mode.kind() == kind));
+        }
+        assert_eq!(table.len(), 7);
+    }
+
+    #[test]
+    fn test_odds_table_distinct_localized_names() {
+        for lang in [Language::Ru, Language::En] {
+            let names: HashSet<&str> = DiceGame::odds_table(6, lang)
+                .iter()
+                .map(|row| row.name)
+                .collect();
+            assert_eq!(names.len(), 7);
+        }
+        let english = DiceGame::odds_table(6, Language::En);
+        assert_eq!(english[0].name, "Even");
+        assert_eq!(english[1].name, "Odd");
+    }
+
+    #[test]
+    fn test_odds_table_fair_rows() {
+        for row in DiceGame::odds_table(6, Language::Ru) {
+            let multiplier = row.fair_multiplier.unwrap();
+            assert!((row.win_probability * multiplier - 1.0).abs() < 1e-9);
+            assert!(!row.name.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_volatility_tier_common_modes() {
+        assert_eq!(
+            DiceGame::volatility_tier(&GameMode::EvenOdd(EvenOddChoice::Even), 6),
+            VolatilityTier::Low
+        );
+        assert_eq!(
+            DiceGame::volatility_tier(&GameMode::Exact(3), 6),
+            VolatilityTier::High
+        );
+    }
+
+    #[test]
+    fn test_volatility_tier_boundaries() {
+        // Дисперсия ровно 1.0 и ровно 3.0 относится к нижнему уровню
+        assert_eq!(
+            DiceGame::volatility_tier(&GameMode::HighLow(HighLowChoice::High), 6),
+            VolatilityTier::Low
+        );
+        assert_eq!(
+            DiceGame::volatility_tier(&GameMode::Exact(2), 4),
+            VolatilityTier::Medium
+        );
+        assert_eq!(
+            DiceGame::volatility_tier(&GameMode::Exact(2), 3),
+            VolatilityTier::Medium
+        );
+    }
+
+    #[test]
+    fn test_teaser_message_close_miss() {
+        assert!(DiceGame::teaser_message(1, Language::Ru).is_some());
+        assert!(DiceGame::teaser_message(1, Language::En).is_some());
+    }
+
+    #[test]
+    fn test_teaser_message_far_miss_and_hit() {
+        assert_eq!(DiceGame::teaser_message(4, Language::Ru), None);
+        assert_eq!(DiceGame::teaser_message(0, Language::Ru), None);
+    }
+
+    #[test]
+    fn test_win_message_for_reproducible() {
+        for nonce in 0..10 {
+            assert_eq!(
+                DiceGame::win_message_for(42, nonce),
+                DiceGame::win_message_for(42, nonce)
+            );
+        }
+    }
+
+    #[test]
+    fn test_win_message_for_players_differ() {
+        let sequence = |player| {
+            (0..5)
+                .map(|nonce| DiceGame::win_message_for(player, nonce))
+                .collect::<Vec<_>>()
+        };
+        assert_ne!(sequence(1), sequence(2));
+        // Каждый игрок видит все сообщения пула за один цикл
+        let mut messages = sequence(1);
+        messages.sort();
+        let mut pool = WIN_MESSAGES_RU.to_vec();
+        pool.sort();
+        assert_eq!(messages, pool);
+    }
+
+    #[test]
+    fn test_message_pool_random_win_for() {
+        let russian = MessagePool::default();
+        let english = MessagePool { lang: Language::En };
+        for nonce in 0..5 {
+            assert_eq!(
+                russian.random_win_for(7, nonce),
+                DiceGame::win_message_for(7, nonce)
+            );
+            assert_eq!(
+                english.random_win_for(7, nonce),
+                english.random_win_for(7, nonce)
+            );
+            assert!(WIN_MESSAGES_EN.contains(&english.random_win_for(7, nonce)));
+        }
+    }
+
+    #[test]
+    fn test_round_net_win() {
+        assert_eq!(DiceGame::round_net(true, 100, 2.0), 100);
+        assert_eq!(DiceGame::round_net(true, 333, 1.5), 166);
+    }
+
+    #[test]
+    fn test_round_net_loss() {
+        assert_eq!(DiceGame::round_net(false, 100, 2.0), -100);
+        assert_eq!(DiceGame::round_net(false, u64::MAX, 2.0), -i64::MAX);
+    }
+
+    #[test]
+    fn test_streak_probability_three_wins() {
+        assert!((DiceGame::streak_probability(0.5, 3) - 0.125).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_streak_probability_zero_length() {
+        assert_eq!(DiceGame::streak_probability(0.3, 0), 1.0);
+    }
+
+    #[test]
+    fn test_optimal_stop_round_aversion_stops_earlier() {
+        let neutral = DiceGame::optimal_stop_round(0.8, 0.0);
+        let moderate = DiceGame::optimal_stop_round(0.8, 1.0);
+        let cautious = DiceGame::optimal_stop_round(0.8, 3.0);
+        assert_eq!(neutral, MAX_RECOMMENDED_DOUBLINGS);
+        assert_eq!(moderate, 4);
+        assert_eq!(cautious, 0);
+    }
+
+    #[test]
+    fn test_optimal_stop_round_fair_game() {
+        // Честная игра 50/50 не дает выгоды от удвоений
+        assert_eq!(DiceGame::optimal_stop_round(0.5, 0.0), 0);
+        assert_eq!(DiceGame::optimal_stop_round(0.5, 1.0), 0);
+        assert_eq!(DiceGame::optimal_stop_round(f64::NAN, 1.0), 0);
+    }
+
+    #[test]
+    fn test_prob_at_least_one_win_exact_number() {
+        let one = DiceGame::prob_at_least_one_win(1.0 / 6.0, 1);
+        assert!((one - 1.0 / 6.0).abs() < 1e-12);
+        let ten = DiceGame::prob_at_least_one_win(1.0 / 6.0, 10);
+        assert!((ten - (1.0 - (5.0f64 / 6.0).powi(10))).abs() < 1e-12);
+        assert!((ten - 0.8385).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_prob_at_least_one_win_zero_tries() {
+        assert_eq!(DiceGame::prob_at_least_one_win(0.5, 0), 0.0);
+    }
+
+    #[test]
+    fn test_probability_of_ruin_fair_game() {
+        assert!((DiceGame::probability_of_ruin(0.5, 5, 10) - 0.5).abs() < 1e-12);
+        assert!((DiceGame::probability_of_ruin(0.5, 2, 10) - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_probability_of_ruin_unfavorable_game() {
+        // r = 51/49: (r^5 - r^10) / (1 - r^10)
+        let ruin = DiceGame::probability_of_ruin(0.49, 5, 10);
+        assert!((ruin - 0.549_840_599_330_2).abs() < 1e-9);
+        // На большой цели почти наверняка разорение, без переполнения
+        let long_shot = DiceGame::probability_of_ruin(0.49, 10, 100_000);
+        assert!(long_shot > 0.999 && long_shot <= 1.0);
+    }
+
+    #[test]
+    fn test_probability_of_ruin_edges() {
+        assert_eq!(DiceGame::probability_of_ruin(0.4, 10, 10), 0.0);
+        assert_eq!(DiceGame::probability_of_ruin(0.6, 0, 10), 1.0);
+        assert_eq!(DiceGame::probability_of_ruin(0.0, 5, 10), 1.0);
+    }
+
+    #[test]
+    fn test_surprise_bits_exact_win() {
+        let bits = DiceGame::surprise_bits(1.0 / 6.0, true);
+        assert!((bits - 2.585).abs() < 1e-3);
+        assert!(DiceGame::surprise_bits(1.0 / 6.0, false) < bits);
+    }
+
+    #[test]
+    fn test_surprise_bits_certain_outcome() {
+        assert_eq!(DiceGame::surprise_bits(1.0, true), 0.0);
+        assert_eq!(DiceGame::surprise_bits(0.0, false), 0.0);
+    }
+
+    #[test]
+    fn test_expected_rounds_until_broke_unfavorable_game() {
+        let mut rng = StdRng::seed_from_u64(21);
+        let rounds = DiceGame::expected_rounds_until_broke(1.0 / 6.0, 5.0, 100, 10, &mut rng, 500);
+        assert!(rounds.is_finite());
+        assert!(rounds >= 10.0);
+        assert!(rounds < MAX_SIMULATED_ROUNDS as f64);
+    }
+
+    #[test]
+    fn test_expected_rounds_until_broke_capped() {
+        let mut rng = StdRng::seed_from_u64(21);
+        let rounds = DiceGame::expected_rounds_until_broke(1.0, 2.0, 10, 10, &mut rng, 3);
+        assert_eq!(rounds, MAX_SIMULATED_ROUNDS as f64);
+        assert_eq!(
+            DiceGame::expected_rounds_until_broke(0.5, 2.0, 10, 10, &mut rng, 0),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_skill_tier_few_games_novice() {
+        assert_eq!(DiceGame::skill_tier(0.65, 5), SkillTier::Novice);
+        assert_eq!(
+            DiceGame::skill_tier(1.0, SKILL_TIER_MIN_GAMES - 1),
+            SkillTier::Novice
+        );
+    }
+
+    #[test]
+    fn test_skill_tier_many_games() {
+        assert_eq!(DiceGame::skill_tier(0.65, 200), SkillTier::Expert);
+        assert_eq!(DiceGame::skill_tier(0.5, 200), SkillTier::Skilled);
+        assert_eq!(DiceGame::skill_tier(0.8, 200), SkillTier::Lucky);
+        assert_eq!(DiceGame::skill_tier(0.3, 200), SkillTier::Novice);
+    }
+
+    #[test]
+    fn test_median_roll_odd_count() {
+        assert_eq!(DiceGame::median_roll(&[5, 1, 3]), Some(3.0));
+    }
+
+    #[test]
+    fn test_median_roll_even_count() {
+        assert_eq!(DiceGame::median_roll(&[6, 1, 4, 3]), Some(3.5));
+    }
+
+    #[test]
+    fn test_median_roll_empty() {
+        assert_eq!(DiceGame::median_roll(&[]), None);
+    }
+
+    #[test]
+    fn test_split_bet_settles_each_leg() {
+        let mut rng = rand::rngs::mock::StepRng::new(0, 1 << 31);
+        let allocations = [
+            (GameMode::EvenOdd(EvenOddChoice::Odd), 60),
+            (GameMode::Exact(1), 30),
+            (GameMode::HighLow(HighLowChoice::High), 10),
+        ];
+        let settlements = DiceGame::split_bet(100, &allocations, 6, &mut rng).unwrap();
+        assert_eq!(settlements.len(), 3);
+        // Все доли рассчитываются по одному броску
+        assert!(settlements.iter().all(|leg| leg.result.roll == 1));
+        let payouts: Vec<u64> = settlements.iter().map(|leg| leg.payout).collect();
+        assert_eq!(payouts, vec![120, 180, 0]);
+    }
+
+    #[test]
+    fn test_split_bet_exact_integer_payout() {
+        // На d9 ставка «Больше» выигрывает на гранях 5–9, множитель 9/5:
+        // 15 * 9 / 5 = 27 ровно, без потери единицы на округлении
+        let mut rng = rand::rngs::mock::StepRng::new(u64::MAX, 0);
+        let allocations = [(GameMode::HighLow(HighLowChoice::High), 15)];
+        let settlements = DiceGame::split_bet(15, &allocations, 9, &mut rng).unwrap();
+        assert!(settlements[0].result.won);
+        assert_eq!(settlements[0].payout, 27);
+    }
+
+    #[test]
+    fn test_split_bet_mis_summed_allocation() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let allocations = [
+            (GameMode::EvenOdd(EvenOddChoice::Even), 60),
+            (GameMode::Exact(2), 30),
+        ];
+        assert_eq!(
+            DiceGame::split_bet(100, &allocations, 6, &mut rng),
+            Err(BetError::AllocationMismatch {
+                total: 100,
+                allocated: 90,
+            })
+        );
+    }
+
+    #[test]
+    fn test_split_bet_zero_sides() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let allocations = [(GameMode::EvenOdd(EvenOddChoice::Even), 10)];
+        assert_eq!(
+            DiceGame::split_bet(10, &allocations, 0, &mut rng),
+            Err(BetError::NoSides)
+        );
+    }
+
+    #[test]
+    fn test_split_bet_invalid_mode() {
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(
+            DiceGame::split_bet(10, &[(GameMode::Exact(9), 10)], 6, &mut rng),
+            Err(BetError::InvalidMode(ModeError::GuessOutOfRange {
+                guess: 9,
+                sides: 6,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_try_settle_first_succeeds() {
+        let mut guard = ClaimGuard::new(10);
+        assert!(guard.try_settle(1));
+        assert!(guard.try_settle(2));
+    }
+
+    #[test]
+    fn test_try_settle_duplicate_rejected() {
+        let mut guard = ClaimGuard::new(10);
+        assert!(guard.try_settle(42));
+        assert!(!guard.try_settle(42));
+    }
+
+    #[test]
+    fn test_try_settle_evicts_oldest() {
+        let mut guard = ClaimGuard::new(2);
+        assert!(guard.try_settle(1));
+        assert!(guard.try_settle(2));
+        assert!(guard.try_settle(3));
+        // Раунд 1 вытеснен, раунды 2 и 3 все еще помнятся
+        assert!(!guard.try_settle(3));
+        assert!(!guard.try_settle(2));
+        assert!(guard.try_settle(1));
+    }
+
+    #[test]
+    fn test_undo_stack_lifo_order() {
+        let mut stack = UndoStack::new(5);
+        stack.push(GameFsm::SelectingMode);
+        stack.push(GameFsm::SelectingChoice(ModeKind::Exact));
+        stack.push(GameFsm::Rolling(GameMode::Exact(4)));
+        assert_eq!(stack.undo(), Some(GameFsm::Rolling(GameMode::Exact(4))));
+        assert_eq!(
+            stack.undo(),
+            Some(GameFsm::SelectingChoice(ModeKind::Exact))
+        );
+        assert_eq!(stack.undo(), Some(GameFsm::SelectingMode));
+        assert_eq!(stack.undo(), None);
+    }
+
+    #[test]
+    fn test_undo_stack_capped() {
+        let mut stack = UndoStack::new(2);
+        for roll in 1..=4 {
+            stack.push(roll);
+        }
+        assert_eq!(stack.undo(), Some(4));
+        assert_eq!(stack.undo(), Some(3));
+        // Более старые состояния вытеснены
+        assert_eq!(stack.undo(), None);
+    }
+
+    #[test]
+    fn test_daily_free_play_first_play_allowed() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut free_play = DailyFreePlay::new();
+        assert!(free_play.can_play(1, today));
+        assert!(free_play.consume(1, today));
+    }
+
+    #[test]
+    fn test_daily_free_play_second_same_day_blocked() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut free_play = DailyFreePlay::new();
+        free_play.consume(1, today);
+        assert!(!free_play.can_play(1, today));
+        assert!(!free_play.consume(1, today));
+        // Другие игроки не затронуты
+        assert!(free_play.can_play(2, today));
+    }
+
+    #[test]
+    fn test_daily_free_play_next_day_resets() {
+        let today = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let mut free_play = DailyFreePlay::new();
+        free_play.consume(1, today);
+        let tomorrow = today.succ_opt().unwrap();
+        assert!(free_play.can_play(1, tomorrow));
+        assert!(free_play.consume(1, tomorrow));
+    }
+
+    #[test]
+    fn test_bot_strategy_random_valid_choices() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for kind in ModeKind::all() {
+            for _ in 0..20 {
+                let mode = BotStrategy::Random.choose(kind, &mut rng);
+                assert_eq!(mode.kind(), kind);
+                assert_eq!(mode.validate(6), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bot_strategy_smart_biased_die() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let strategy = BotStrategy::Smart {
+            weights: vec![0.3, 0.1, 0.3, 0.1, 0.1, 0.1],
+        };
+        assert_eq!(
+            strategy.choose(ModeKind::EvenOdd, &mut rng),
+            GameMode::EvenOdd(EvenOddChoice::Odd)
+        );
+        assert_eq!(
+            strategy.choose(ModeKind::HighLow, &mut rng),
+            GameMode::HighLow(HighLowChoice::Low)
+        );
+    }
+
+    #[test]
+    fn test_mercy_rule_threshold_triggers() {
+        let mut mercy = MercyRule::new(3);
+        mercy.register(false);
+        mercy.register(false);
+        assert!(!mercy.should_grant());
+        mercy.register(false);
+        assert!(mercy.should_grant());
+    }
+
+    #[test]
+    fn test_mercy_rule_win_resets() {
+        let mut mercy = MercyRule::new(2);
+        mercy.register(false);
+        mercy.register(false);
+        assert!(mercy.should_grant());
+        mercy.register(true);
+        assert_eq!(mercy.active_losses, 0);
+        assert!(!mercy.should_grant());
+    }
+
+    #[test]
+    fn test_pity_timer_losses_raise_boost() {
+        let mut timer = PityTimer::new(0.02, 0.1);
+        assert_eq!(timer.current_boost(), 0.0);
+        timer.register(false);
+        let one = timer.current_boost();
+        timer.register(false);
+        assert!(timer.current_boost() > one);
+        for _ in 0..20 {
+            timer.register(false);
+        }
+        assert_eq!(timer.current_boost(), 0.1);
+    }
+
+    #[test]
+    fn test_pity_timer_win_resets() {
+        let mut timer = PityTimer::new(0.02, 0.1);
+        timer.register(false);
+        timer.register(false);
+        timer.register(true);
+        assert_eq!(timer.current_boost(), 0.0);
+    }
+
+    #[test]
+    fn test_pattern_detector_triple_triggers() {
+        let mut detector = PatternDetector::new(3);
+        assert_eq!(detector.feed(5), None);
+        assert_eq!(detector.feed(5), None);
+        assert_eq!(detector.feed(5), Some(BonusTrigger { face: 5, length: 3 }));
+    }
+
+    #[test]
+    fn test_pattern_detector_broken_sequence() {
+        let mut detector = PatternDetector::new(3);
+        assert_eq!(detector.feed(2), None);
+        assert_eq!(detector.feed(2), None);
+        assert_eq!(detector.feed(4), None);
+        assert_eq!(detector.feed(2), None);
+        assert_eq!(detector.feed(2), None);
+    }
+
+    #[test]
+    fn test_combo_multiplier_repeats_raise() {
+        let mut combo = ComboMultiplier::new(0.5, 3.0);
+        assert_eq!(combo.feed(4), 1.0);
+        assert_eq!(combo.feed(4), 1.5);
+        assert_eq!(combo.feed(4), 2.0);
+    }
+
+    #[test]
+    fn test_combo_multiplier_change_resets() {
+        let mut combo = ComboMultiplier::new(0.5, 3.0);
+        combo.feed(2);
+        combo.feed(2);
+        assert_eq!(combo.feed(5), 1.0);
+        assert_eq!(combo.feed(5), 1.5);
+    }
+
+    #[test]
+    fn test_combo_multiplier_capped() {
+        let mut combo = ComboMultiplier::new(0.5, 3.0);
+        for _ in 0..20 {
+            combo.feed(6);
+        }
+        assert_eq!(combo.feed(6), 3.0);
+    }
+
+    #[test]
+    fn test_reroll_tokens_spend() {
+        let mut tokens = RerollTokens { count: 1 };
+        assert!(tokens.spend());
+        assert_eq!(tokens.count, 0);
+        assert!(!tokens.spend());
+    }
+
+    #[test]
+    fn test_reroll_tokens_reroll() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut tokens = RerollTokens { count: 2 };
+        let roll = tokens.reroll(20, &mut rng).unwrap();
+        assert!((1..=20).contains(&roll));
+        assert_eq!(tokens.count, 1);
+    }
+
+    #[test]
+    fn test_reroll_tokens_empty_blocks_reroll() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut tokens = RerollTokens::default();
+        assert_eq!(tokens.reroll(6, &mut rng), None);
+        assert_eq!(tokens.count, 0);
+    }
+
+    #[test]
+    fn test_winning_filters_wins() {
+        let result = |roll, won| GameResult {
+            mode: GameMode::Exact(roll),
+            roll,
+            won,
+        };
+        let session = vec![
+            result(1, true),
+            result(2, false),
+            result(3, true),
+            result(4, false),
+        ];
+        let wins: Vec<GameResult> = session.into_iter().winning().collect();
+        assert_eq!(wins, vec![result(1, true), result(3, true)]);
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        fn arb_mode(sides: u8) -> impl Strategy<Value = GameMode> {
+            prop_oneof![
+                Just(GameMode::EvenOdd(EvenOddChoice::Even)),
+                Just(GameMode::EvenOdd(EvenOddChoice::Odd)),
+                Just(GameMode::HighLow(HighLowChoice::High)),
+                Just(GameMode::HighLow(HighLowChoice::Low)),
+                Just(GameMode::GuessOne(GuessOneChoice::Yes)),
+                Just(GameMode::GuessOne(GuessOneChoice::No)),
+                (1..=sides).prop_map(GameMode::Exact),
+            ]
+        }
+
+        fn arb_sided_mode() -> impl Strategy<Value = (u8, GameMode)> {
+            (2u8..=20u8).prop_flat_map(|sides| (Just(sides), arb_mode(sides)))
+        }
+
+        proptest! {
+            #[test]
+            fn fair_payout_property((sides, mode) in arb_sided_mode()) {
+                let probability = DiceGame::win_probability(&mode, sides);
+                let multiplier = DiceGame::fair_multiplier(&mode, sides).unwrap();
+                prop_assert!((probability * multiplier - 1.0).abs() < 1e-9);
+            }
+
+            #[test]
+            fn even_odd_property(dice_result in 1u8..=6u8) {
+                let is_even = dice_result.is_multiple_of(2);
+                prop_assert_eq!(DiceGame::check_even_odd(dice_result, EvenOddChoice::Even), is_even);
+                prop_assert_eq!(DiceGame::check_even_odd(dice_result, EvenOddChoice::Odd), !is_even);
+            }
+
+            #[test]
+            fn high_low_property(dice_result in 1u8..=6u8) {
+                let is_high = dice_result >= 4;
+                prop_assert_eq!(DiceGame::check_high_low(dice_result, HighLowChoice::High), is_high);
+                prop_assert_eq!(DiceGame::check_high_low(dice_result, HighLowChoice::Low), !is_high);
+            }
+
+            #[test]
+            fn within_zero_tolerance_matches_exact(dice_result in 1u8..=6u8, guess in 1u8..=6u8) {
+                prop_assert_eq!(
+                    DiceGame::check_within(dice_result, guess, 0),
+                    DiceGame::check_exact_number(dice_result, guess)
+                );
+            }
+
+            #[test]
+            fn sum_parity_property(half_sides in 1u8..=10u8) {
+                let sides = half_sides * 2;
+                let mut even_wins = 0u32;
+                let mut odd_wins = 0u32;
+                for die_a in 1..=sides {
+                    for die_b in 1..=sides {
+                        if DiceGame::check_sum_parity(die_a, die_b, EvenOddChoice::Even) {
+                            even_wins += 1;
+                        }
+                        if DiceGame::check_sum_parity(die_a, die_b, EvenOddChoice::Odd) {
+                            odd_wins += 1;
+                        }
+                    }
+                }
+                prop_assert_eq!(even_wins, odd_wins);
+                prop_assert_eq!(even_wins + odd_wins, sides as u32 * sides as u32);
+            }
+
+            #[test]
+            fn check_mode_matches_d6_checks(dice_result in 1u8..=6u8) {
+                prop_assert_eq!(
+                    DiceGame::check_mode(&GameMode::HighLow(HighLowChoice::High), dice_result, 6),
+                    DiceGame::check_high_low(dice_result, HighLowChoice::High)
+                );
+                prop_assert_eq!(
+                    DiceGame::check_mode(&GameMode::HighLow(HighLowChoice::Low), dice_result, 6),
+                    DiceGame::check_high_low(dice_result, HighLowChoice::Low)
+                );
+            }
+
+            #[test]
+            fn exact_number_property(dice_result in 1u8..=6u8) {
+                prop_assert!(DiceGame::check_exact_number(dice_result, dice_result));
+                let other = if dice_result == 6 { 1 } else { dice_result + 1 };
+                prop_assert!(!DiceGame::check_exact_number(dice_result, other));
+            }
+
+            #[test]
+            fn frame_delays_property(count in 1usize..=30, total_ms in 0u64..=5000) {
+                let delays = DiceGame::frame_delays(count, total_ms);
+                prop_assert_eq!(delays.iter().sum::<u64>(), total_ms);
+                let ramp_sum = count as u64 * (count as u64 + 1) / 2;
+                if total_ms >= ramp_sum {
+                    prop_assert!(delays.windows(2).all(|pair| pair[0] < pair[1]));
+                    prop_assert!(delays[0] > 0);
+                } else {
+                    prop_assert!(delays.windows(2).all(|pair| pair[0] <= pair[1]));
+                }
             }
         }
     }
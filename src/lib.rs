@@ -0,0 +1,11 @@
+pub mod achievements;
+pub mod elo;
+pub mod events;
+pub mod fairness;
+pub mod game;
+pub mod multiplayer;
+pub mod odds;
+pub mod render;
+pub mod rng;
+pub mod state;
+pub mod storage;
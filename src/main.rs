@@ -4,8 +4,6 @@ use std::net::SocketAddr;
 use teloxide::prelude::*;
 
 mod bot;
-mod game;
-mod state;
 
 use bot::BotHandler;
 
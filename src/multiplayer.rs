@@ -0,0 +1,276 @@
+use rand::Rng;
+use std::cmp::Ordering;
+
+use crate::game::DiceGame;
+use crate::state::PlayerId;
+
+/// Итог дуэли на ставках между двумя игроками
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settlement {
+    pub rolls: [(PlayerId, u8); 2],    // Броски обоих игроков
+    pub winner: Option<PlayerId>,      // None при ничьей
+    pub payouts: Vec<(PlayerId, u64)>, // Выплаты из банка
+}
+
+/// Банк ставок для дуэли двух игроков
+///
+/// Обе ставки равны и хранятся до розыгрыша; выплаты никогда не
+/// превышают внесенную сумму.
+pub struct WagerPool {
+    players: [PlayerId; 2],
+    pot: u64,
+}
+
+impl WagerPool {
+    /// Создание банка из двух равных ставок
+    pub fn new(first: PlayerId, second: PlayerId, stake: u64) -> Self {
+        Self {
+            players: [first, second],
+            pot: stake.saturating_mul(2),
+        }
+    }
+
+    /// Сумма в банке
+    pub fn pot(&self) -> u64 {
+        self.pot
+    }
+
+    /// Розыгрыш банка на кубиках с `sides` гранями: победитель забирает
+    /// все, при ничьей банк делится
+    ///
+    /// Исход определяет `DiceGame::duel_outcome`, где первый игрок
+    /// занимает место компьютера. Повторный розыгрыш уже выплаченного
+    /// банка ничего не выплачивает.
+    pub fn settle(&mut self, sides: u8, rng: &mut impl Rng) -> Settlement {
+        let [first, second] = self.players;
+        let first_roll = rng.gen_range(1..=sides.max(1));
+        let second_roll = rng.gen_range(1..=sides.max(1));
+        let pot = std::mem::take(&mut self.pot);
+
+        let (winner, payouts) = match DiceGame::duel_outcome(first_roll, second_roll) {
+            Ordering::Greater => (Some(first), vec![(first, pot)]),
+            Ordering::Less => (Some(second), vec![(second, pot)]),
+            Ordering::Equal => {
+                let half = pot / 2;
+                (None, vec![(first, pot - half), (second, half)])
+            }
+        };
+
+        Settlement {
+            rolls: [(first, first_roll), (second, second_roll)],
+            winner,
+            payouts,
+        }
+    }
+}
+
+/// Ошибка хода в матче двух игроков
+#[derive(Clone, Debug, PartialEq)]
+pub enum TurnError {
+    OutOfTurn { expected: PlayerId }, // Сейчас ход другого игрока
+    MatchOver,                        // Матч уже завершен
+}
+
+/// Очередность ходов в матче двух игроков
+///
+/// Игроки бросают по очереди; после второго броска матч завершается
+/// сравнением результатов через `DiceGame::duel_outcome`, как и розыгрыш
+/// `WagerPool`: первый игрок занимает место компьютера.
+pub struct TurnManager {
+    players: [PlayerId; 2],
+    rolls: Vec<u8>,
+    winners: Option<Vec<PlayerId>>,
+}
+
+impl TurnManager {
+    pub fn new(first: PlayerId, second: PlayerId) -> Self {
+        Self {
+            players: [first, second],
+            rolls: Vec::with_capacity(2),
+            winners: None,
+        }
+    }
+
+    /// Игрок, чей сейчас ход; `None`, если матч завершен
+    pub fn current_player(&self) -> Option<PlayerId> {
+        self.players.get(self.rolls.len()).copied()
+    }
+
+    /// Учет броска игрока
+    pub fn submit(&mut self, player: PlayerId, roll: u8) -> Result<(), TurnError> {
+        let expected = self.current_player().ok_or(TurnError::MatchOver)?;
+        if player != expected {
+            return Err(TurnError::OutOfTurn { expected });
+        }
+        self.rolls.push(roll);
+        if let [first_roll, second_roll] = self.rolls[..] {
+            let [first, second] = self.players;
+            self.winners = Some(match DiceGame::duel_outcome(first_roll, second_roll) {
+                Ordering::Greater => vec![first],
+                Ordering::Less => vec![second],
+                Ordering::Equal => vec![first, second],
+            });
+        }
+        Ok(())
+    }
+
+    /// Победители завершенного матча (оба игрока при ничьей)
+    pub fn winners(&self) -> Option<&[PlayerId]> {
+        self.winners.as_deref()
+    }
+}
+
+/// Личное противостояние двух игроков для предложения реванша
+pub struct Rivalry {
+    players: [PlayerId; 2],
+    wins: [u32; 2],
+}
+
+impl Rivalry {
+    pub fn new(first: PlayerId, second: PlayerId) -> Self {
+        Self {
+            players: [first, second],
+            wins: [0, 0],
+        }
+    }
+
+    /// Учет победы; победы посторонних игроков игнорируются
+    pub fn record(&mut self, winner: PlayerId) {
+        if let Some(index) = self.players.iter().position(|&player| player == winner) {
+            self.wins[index] = self.wins[index].saturating_add(1);
+        }
+    }
+
+    /// Счет личных встреч `(победы первого, победы второго)`
+    pub fn head_to_head(&self) -> (u32, u32) {
+        (self.wins[0], self.wins[1])
+    }
+
+    /// Стоит ли предложить реванш: счет отличается не больше чем на одну победу
+    pub fn suggest_rematch(&self) -> bool {
+        self.wins[0].abs_diff(self.wins[1]) <= 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_wager_pool_winner_takes_pot() {
+        // Первый бросок дает 1, второй — 4
+        let mut rng = rand::rngs::mock::StepRng::new(0, 1 << 31);
+        let mut pool = WagerPool::new(1, 2, 50);
+        assert_eq!(pool.pot(), 100);
+
+        let settlement = pool.settle(6, &mut rng);
+        assert_eq!(settlement.rolls, [(1, 1), (2, 4)]);
+        assert_eq!(settlement.winner, Some(2));
+        assert_eq!(settlement.payouts, vec![(2, 100)]);
+        assert_eq!(pool.pot(), 0);
+    }
+
+    #[test]
+    fn test_wager_pool_tie_splits_pot() {
+        let mut roller = rand::rngs::mock::StepRng::new(0, 0);
+        let mut pool = WagerPool::new(1, 2, 50);
+
+        let settlement = pool.settle(6, &mut roller);
+        assert_eq!(settlement.winner, None);
+        assert_eq!(settlement.payouts, vec![(1, 50), (2, 50)]);
+    }
+
+    #[test]
+    fn test_wager_pool_rolls_use_sides() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let rolls: Vec<u8> = (0..50)
+            .flat_map(|_| WagerPool::new(1, 2, 10).settle(20, &mut rng).rolls)
+            .map(|(_, roll)| roll)
+            .collect();
+        assert!(rolls.iter().all(|roll| (1..=20).contains(roll)));
+        assert!(rolls.iter().any(|&roll| roll > 6));
+    }
+
+    #[test]
+    fn test_wager_pool_pays_out_once() {
+        let mut rng = StdRng::seed_from_u64(45);
+        let mut pool = WagerPool::new(1, 2, 50);
+        let first: u64 = pool
+            .settle(6, &mut rng)
+            .payouts
+            .iter()
+            .map(|(_, a)| a)
+            .sum();
+        let second: u64 = pool
+            .settle(6, &mut rng)
+            .payouts
+            .iter()
+            .map(|(_, a)| a)
+            .sum();
+        assert_eq!(first, 100);
+        assert_eq!(second, 0);
+    }
+
+    #[test]
+    fn test_turn_manager_two_turns_resolve() {
+        let mut turns = TurnManager::new(10, 20);
+        assert_eq!(turns.current_player(), Some(10));
+        assert_eq!(turns.submit(10, 2), Ok(()));
+        assert_eq!(turns.current_player(), Some(20));
+        assert_eq!(turns.winners(), None);
+        assert_eq!(turns.submit(20, 5), Ok(()));
+        assert_eq!(turns.current_player(), None);
+        assert_eq!(turns.winners(), Some(&[20][..]));
+    }
+
+    #[test]
+    fn test_turn_manager_draw() {
+        let mut turns = TurnManager::new(10, 20);
+        turns.submit(10, 4).unwrap();
+        turns.submit(20, 4).unwrap();
+        assert_eq!(turns.winners(), Some(&[10, 20][..]));
+    }
+
+    #[test]
+    fn test_turn_manager_out_of_turn() {
+        let mut turns = TurnManager::new(10, 20);
+        assert_eq!(
+            turns.submit(20, 3),
+            Err(TurnError::OutOfTurn { expected: 10 })
+        );
+        turns.submit(10, 3).unwrap();
+        turns.submit(20, 1).unwrap();
+        assert_eq!(turns.submit(10, 6), Err(TurnError::MatchOver));
+    }
+
+    #[test]
+    fn test_rivalry_head_to_head() {
+        let mut rivalry = Rivalry::new(1, 2);
+        for winner in [1, 2, 1, 1, 99] {
+            rivalry.record(winner);
+        }
+        assert_eq!(rivalry.head_to_head(), (3, 1));
+    }
+
+    #[test]
+    fn test_rivalry_suggest_rematch_close() {
+        let mut rivalry = Rivalry::new(1, 2);
+        assert!(rivalry.suggest_rematch());
+        rivalry.record(1);
+        rivalry.record(2);
+        rivalry.record(1);
+        assert!(rivalry.suggest_rematch());
+    }
+
+    #[test]
+    fn test_rivalry_suggest_rematch_one_sided() {
+        let mut rivalry = Rivalry::new(1, 2);
+        for _ in 0..3 {
+            rivalry.record(2);
+        }
+        assert!(!rivalry.suggest_rematch());
+    }
+}
@@ -0,0 +1,109 @@
+/// Минимальная вероятность, к которой приводятся некорректные значения
+const MIN_PROBABILITY: f64 = 0.001;
+
+/// Наибольший знаменатель дробных коэффициентов
+const MAX_DENOMINATOR: u32 = 100;
+
+/// Приведение вероятности к диапазону, в котором коэффициенты конечны
+fn clamp_probability(prob: f64) -> f64 {
+    if prob.is_nan() {
+        return 0.5;
+    }
+    prob.clamp(MIN_PROBABILITY, 1.0 - MIN_PROBABILITY)
+}
+
+/// Десятичный коэффициент (полная выплата на единицу ставки)
+pub fn to_decimal(prob: f64) -> f64 {
+    1.0 / clamp_probability(prob)
+}
+
+/// Дробный коэффициент "выигрыш к ставке", например `(5, 1)` для 1/6
+///
+/// Дробь подбирается цепными дробями со знаменателем не больше 100. Для
+/// фаворитов с выигрышем меньше 1/100 возвращается `(1, 100)`, чтобы
+/// коэффициент не превратился в нулевой `0/1`.
+pub fn to_fractional(prob: f64) -> (u32, u32) {
+    let prob = clamp_probability(prob);
+    let target = (1.0 - prob) / prob;
+
+    // Подходящие дроби цепной дроби для target
+    let (mut prev_num, mut num) = (1u64, target.floor() as u64);
+    let (mut prev_den, mut den) = (0u64, 1u64);
+    let mut rest = target - target.floor();
+    while rest > 1e-9 {
+        let inverse = 1.0 / rest;
+        let term = inverse.floor() as u64;
+        let next_den = term * den + prev_den;
+        if next_den > MAX_DENOMINATOR as u64 {
+            break;
+        }
+        (prev_num, num) = (num, term * num + prev_num);
+        (prev_den, den) = (den, next_den);
+        rest = inverse - inverse.floor();
+    }
+    if num == 0 {
+        return (1, MAX_DENOMINATOR);
+    }
+    (num as u32, den as u32)
+}
+
+/// Американский коэффициент: `+N` — выигрыш со ставки 100,
+/// `-N` — ставка для выигрыша 100; 50% соответствует `+100`
+pub fn to_american(prob: f64) -> i32 {
+    let prob = clamp_probability(prob);
+    if prob > 0.5 {
+        -(prob / (1.0 - prob) * 100.0).round() as i32
+    } else {
+        ((1.0 - prob) / prob * 100.0).round() as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const EXACT_NUMBER: f64 = 1.0 / 6.0;
+
+    #[test]
+    fn test_to_decimal_exact_number() {
+        assert!((to_decimal(EXACT_NUMBER) - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_to_fractional_exact_number() {
+        assert_eq!(to_fractional(EXACT_NUMBER), (5, 1));
+    }
+
+    #[test]
+    fn test_to_american_exact_number() {
+        assert_eq!(to_american(EXACT_NUMBER), 500);
+    }
+
+    #[test]
+    fn test_even_money() {
+        assert!((to_decimal(0.5) - 2.0).abs() < 1e-9);
+        assert_eq!(to_fractional(0.5), (1, 1));
+        assert_eq!(to_american(0.5), 100);
+    }
+
+    #[test]
+    fn test_favorite() {
+        assert_eq!(to_fractional(0.75), (1, 3));
+        assert_eq!(to_american(0.75), -300);
+    }
+
+    #[test]
+    fn test_to_fractional_heavy_favorite() {
+        assert_eq!(to_fractional(0.995), (1, MAX_DENOMINATOR));
+        assert_eq!(to_fractional(0.999), (1, MAX_DENOMINATOR));
+    }
+
+    #[test]
+    fn test_invalid_probabilities_clamped() {
+        assert!(to_decimal(0.0).is_finite());
+        assert!((to_decimal(-1.0) - 1000.0).abs() < 1e-6);
+        assert!(to_decimal(2.0) > 1.0);
+        assert_eq!(to_american(f64::NAN), 100);
+    }
+}
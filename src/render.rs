@@ -0,0 +1,45 @@
+/// Способ отображения грани кубика в тексте сообщений
+pub trait FaceRenderer {
+    fn render(&self, value: u8) -> String;
+}
+
+/// Грань в виде точек: 3 → "●●●"
+///
+/// Символы граней ⚀–⚅ не используются: ⚀ плохо отображается в некоторых
+/// клиентах.
+pub struct UnicodeDotRenderer;
+
+impl FaceRenderer for UnicodeDotRenderer {
+    fn render(&self, value: u8) -> String {
+        "●".repeat(value as usize)
+    }
+}
+
+/// Грань в виде числа: 3 → "3"
+pub struct NumericRenderer;
+
+impl FaceRenderer for NumericRenderer {
+    fn render(&self, value: u8) -> String {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_unicode_dot_renderer_faces() {
+        let faces: Vec<String> = (1..=6)
+            .map(|value| UnicodeDotRenderer.render(value))
+            .collect();
+        assert_eq!(faces, vec!["●", "●●", "●●●", "●●●●", "●●●●●", "●●●●●●"]);
+    }
+
+    #[test]
+    fn test_numeric_renderer_faces() {
+        let faces: Vec<String> = (1..=6).map(|value| NumericRenderer.render(value)).collect();
+        assert_eq!(faces, vec!["1", "2", "3", "4", "5", "6"]);
+    }
+}
@@ -0,0 +1,365 @@
+use rand::distributions::Uniform;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Источник бросков кубика
+pub trait Roller {
+    /// Бросок кубика с `sides` гранями
+    fn roll(&mut self, sides: u8) -> u8;
+}
+
+impl<R: Rng> Roller for R {
+    fn roll(&mut self, sides: u8) -> u8 {
+        self.gen_range(1..=sides.max(1))
+    }
+}
+
+/// Ошибка проверки броска
+#[derive(Clone, Debug, PartialEq)]
+pub enum RollError {
+    OutOfRange { value: u8, sides: u8 }, // Бросок вне диапазона 1..=sides
+}
+
+/// Бросок с проверкой, что значение попадает в `1..=sides`
+///
+/// Защищает от ошибок в пользовательских реализациях `Roller`.
+pub fn validating_roll(roller: &mut dyn Roller, sides: u8) -> Result<u8, RollError> {
+    let value = roller.roll(sides);
+    if (1..=sides).contains(&value) {
+        Ok(value)
+    } else {
+        Err(RollError::OutOfRange { value, sides })
+    }
+}
+
+/// Заполнение готового буфера бросками кубика с `sides` гранями
+///
+/// Для симуляций: распределение строится один раз, а броски пишутся прямо
+/// в срез без выделения памяти.
+pub fn fill_rolls(buf: &mut [u8], sides: u8, rng: &mut impl Rng) {
+    let faces = Uniform::new_inclusive(1, sides.max(1));
+    for (slot, roll) in buf.iter_mut().zip(rng.sample_iter(faces)) {
+        *slot = roll;
+    }
+}
+
+/// Генератор, объединяющий два источника через XOR
+///
+/// Смесь надежна лишь настолько, насколько надежен более слабый из
+/// источников, и только при их независимости: два одинаково засеянных
+/// генератора дадут одни нули, а коррелированные источники ослабят друг
+/// друга. Никаких гарантий сверх этого XOR не добавляет.
+pub struct BlendedRng<A: RngCore, B: RngCore> {
+    first: A,
+    second: B,
+}
+
+impl<A: RngCore, B: RngCore> BlendedRng<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A: RngCore, B: RngCore> RngCore for BlendedRng<A, B> {
+    fn next_u32(&mut self) -> u32 {
+        self.first.next_u32() ^ self.second.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.first.next_u64() ^ self.second.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.first.fill_bytes(dest);
+        let mut mask = vec![0u8; dest.len()];
+        self.second.fill_bytes(&mut mask);
+        for (byte, mask) in dest.iter_mut().zip(mask) {
+            *byte ^= mask;
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Сохраненное состояние `ResumableRng`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RngState {
+    pub seed: [u8; 32],
+    pub stream: u64,
+    pub word_pos: u128, // Позиция в потоке ChaCha
+}
+
+/// Генератор, состояние которого можно сохранить и восстановить
+///
+/// Позволяет приостановить сессию и продолжить ее с того же места потока:
+/// после восстановления броски совпадают с непрерванной последовательностью.
+pub struct ResumableRng {
+    rng: ChaCha8Rng,
+}
+
+impl ResumableRng {
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Снимок текущего состояния
+    pub fn save_rng_state(&self) -> RngState {
+        RngState {
+            seed: self.rng.get_seed(),
+            stream: self.rng.get_stream(),
+            word_pos: self.rng.get_word_pos(),
+        }
+    }
+
+    /// Возврат к сохраненному состоянию
+    pub fn restore_rng_state(&mut self, state: RngState) {
+        let mut rng = ChaCha8Rng::from_seed(state.seed);
+        rng.set_stream(state.stream);
+        rng.set_word_pos(state.word_pos);
+        self.rng = rng;
+    }
+}
+
+impl RngCore for ResumableRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+/// Буфер заранее сгенерированных бросков для нагруженных серверов
+///
+/// Заполняется целиком при создании и повторно, когда опустеет.
+pub struct RollBuffer<R: Rng> {
+    rng: R,
+    sides: u8,
+    capacity: usize,
+    rolls: VecDeque<u8>,
+}
+
+impl<R: Rng> RollBuffer<R> {
+    pub fn new(rng: R, sides: u8, capacity: usize) -> Self {
+        let mut buffer = Self {
+            rng,
+            sides: sides.max(1),
+            capacity: capacity.max(1),
+            rolls: VecDeque::with_capacity(capacity.max(1)),
+        };
+        buffer.refill();
+        buffer
+    }
+
+    /// Получение следующего броска из буфера
+    pub fn pop(&mut self) -> u8 {
+        if self.rolls.is_empty() {
+            self.refill();
+        }
+        // После refill буфер содержит хотя бы один бросок
+        self.rolls.pop_front().unwrap()
+    }
+
+    fn refill(&mut self) {
+        while self.rolls.len() < self.capacity {
+            let roll = self.rng.gen_range(1..=self.sides);
+            self.rolls.push_back(roll);
+        }
+    }
+}
+
+/// Равномерная выборка фиксированного размера из длинного потока (алгоритм R)
+pub struct ReservoirSampler<T> {
+    capacity: usize,
+    seen: usize,
+    items: Vec<T>,
+}
+
+impl<T> ReservoirSampler<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            seen: 0,
+            items: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Предложить элемент потока для выборки
+    pub fn offer(&mut self, item: T, rng: &mut impl Rng) {
+        self.seen += 1;
+        if self.items.len() < self.capacity {
+            self.items.push(item);
+            return;
+        }
+        let index = rng.gen_range(0..self.seen);
+        if index < self.capacity {
+            self.items[index] = item;
+        }
+    }
+
+    /// Текущая выборка
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Сломанный источник, всегда возвращающий 0
+    struct BrokenRoller;
+
+    impl Roller for BrokenRoller {
+        fn roll(&mut self, _sides: u8) -> u8 {
+            0
+        }
+    }
+
+    #[test]
+    fn test_validating_roll_in_range() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..50 {
+            let value = validating_roll(&mut rng, 6).unwrap();
+            assert!((1..=6).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_validating_roll_broken_roller() {
+        assert_eq!(
+            validating_roll(&mut BrokenRoller, 6),
+            Err(RollError::OutOfRange { value: 0, sides: 6 })
+        );
+    }
+
+    #[test]
+    fn test_fill_rolls_large_buffer_in_range() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let mut buf = vec![0u8; 1_000_000];
+        fill_rolls(&mut buf, 6, &mut rng);
+        assert!(buf.iter().all(|roll| (1..=6).contains(roll)));
+        // На миллионе бросков должны встретиться все грани
+        for face in 1..=6 {
+            assert!(buf.contains(&face));
+        }
+    }
+
+    #[test]
+    fn test_blended_rng_rolls_in_range() {
+        let mut rng = BlendedRng::new(StdRng::seed_from_u64(1), StdRng::seed_from_u64(2));
+        for _ in 0..1000 {
+            let value = validating_roll(&mut rng, 6).unwrap();
+            assert!((1..=6).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_blended_rng_differs_from_sources() {
+        let rolls = |roller: &mut dyn Roller| (0..50).map(|_| roller.roll(6)).collect::<Vec<_>>();
+        let blended = rolls(&mut BlendedRng::new(
+            StdRng::seed_from_u64(1),
+            StdRng::seed_from_u64(2),
+        ));
+        assert_ne!(blended, rolls(&mut StdRng::seed_from_u64(1)));
+        assert_ne!(blended, rolls(&mut StdRng::seed_from_u64(2)));
+    }
+
+    #[test]
+    fn test_resumable_rng_restore_continues_stream() {
+        let mut untouched = ResumableRng::seed_from_u64(77);
+        let expected: Vec<u8> = (0..40).map(|_| untouched.roll(6)).collect();
+
+        let mut rng = ResumableRng::seed_from_u64(77);
+        let mut rolls: Vec<u8> = (0..13).map(|_| rng.roll(6)).collect();
+        let state = rng.save_rng_state();
+        let json = serde_json::to_string(&state).unwrap();
+
+        // Генератор продолжает работать, затем сессия восстанавливается
+        for _ in 0..100 {
+            rng.roll(6);
+        }
+        rng.restore_rng_state(serde_json::from_str(&json).unwrap());
+        rolls.extend((13..40).map(|_| rng.roll(6)));
+        assert_eq!(rolls, expected);
+    }
+
+    #[test]
+    fn test_roll_buffer_values_in_range() {
+        let mut buffer = RollBuffer::new(StdRng::seed_from_u64(5), 6, 16);
+        assert_eq!(buffer.rolls.len(), 16);
+        for _ in 0..100 {
+            assert!((1..=6).contains(&buffer.pop()));
+        }
+    }
+
+    #[test]
+    fn test_roll_buffer_refills_after_exhaustion() {
+        let mut buffer = RollBuffer::new(StdRng::seed_from_u64(5), 6, 4);
+        for _ in 0..4 {
+            buffer.pop();
+        }
+        assert!(buffer.rolls.is_empty());
+        assert!((1..=6).contains(&buffer.pop()));
+        assert_eq!(buffer.rolls.len(), 3);
+    }
+
+    #[test]
+    fn test_reservoir_sampler_holds_capacity() {
+        let mut rng = StdRng::seed_from_u64(50);
+        let mut sampler = ReservoirSampler::new(5);
+        for item in 0..1000 {
+            sampler.offer(item, &mut rng);
+        }
+        assert_eq!(sampler.items().len(), 5);
+    }
+
+    #[test]
+    fn test_reservoir_sampler_fewer_items_than_capacity() {
+        let mut rng = StdRng::seed_from_u64(50);
+        let mut sampler = ReservoirSampler::new(5);
+        for item in 0..3 {
+            sampler.offer(item, &mut rng);
+        }
+        assert_eq!(sampler.items(), &[0, 1, 2]);
+    }
+
+    #[test]
+    fn test_reservoir_sampler_equal_inclusion() {
+        let mut rng = StdRng::seed_from_u64(50);
+        let trials = 3000;
+        let mut counts = [0u32; 10];
+        for _ in 0..trials {
+            let mut sampler = ReservoirSampler::new(3);
+            for item in 0..10 {
+                sampler.offer(item, &mut rng);
+            }
+            for &item in sampler.items() {
+                counts[item] += 1;
+            }
+        }
+        // Ожидаемое число включений каждого элемента: 3000 * 3 / 10 = 900
+        for count in counts {
+            assert!((750..=1050).contains(&count), "count = {}", count);
+        }
+    }
+}
@@ -1,20 +1,424 @@
+use serde::{Deserialize, Serialize};
+
+/// Идентификатор игрока (совпадает с идентификатором пользователя Telegram)
+pub type PlayerId = u64;
+
 /// Выбор пользователя в игре "Четное/Нечетное"
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum EvenOddChoice {
     Even, // Четное
     Odd,  // Нечетное
 }
 
+impl EvenOddChoice {
+    /// Все варианты выбора
+    pub fn all() -> [EvenOddChoice; 2] {
+        [EvenOddChoice::Even, EvenOddChoice::Odd]
+    }
+}
+
 /// Выбор пользователя в игре "Больше/Меньше 3.5"
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum HighLowChoice {
     High, // Больше 3.5 (4-6)
     Low,  // Меньше 3.5 (1-3)
 }
 
+impl HighLowChoice {
+    /// Все варианты выбора
+    pub fn all() -> [HighLowChoice; 2] {
+        [HighLowChoice::High, HighLowChoice::Low]
+    }
+}
+
 /// Выбор пользователя в игре "Угадать единицу"
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum GuessOneChoice {
     Yes, // Выпадет единица
     No,  // Не выпадет единица
 }
+
+impl GuessOneChoice {
+    /// Все варианты выбора
+    pub fn all() -> [GuessOneChoice; 2] {
+        [GuessOneChoice::Yes, GuessOneChoice::No]
+    }
+}
+
+/// Режим игры вместе с выбором пользователя
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GameMode {
+    EvenOdd(EvenOddChoice),   // Четное/Нечетное
+    HighLow(HighLowChoice),   // Больше/Меньше середины кубика
+    Exact(u8),                // Точное число
+    GuessOne(GuessOneChoice), // Угадать единицу
+}
+
+impl GameMode {
+    /// Проверка, что выбор допустим для кубика с `sides` гранями
+    ///
+    /// Четное/Нечетное, Больше/Меньше и Угадать единицу всегда допустимы.
+    pub fn validate(&self, sides: u8) -> Result<(), ModeError> {
+        match self {
+            GameMode::Exact(guess) if !(1..=sides).contains(guess) => {
+                Err(ModeError::GuessOutOfRange {
+                    guess: *guess,
+                    sides,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Вид режима без выбора пользователя
+    pub fn kind(&self) -> ModeKind {
+        match self {
+            GameMode::EvenOdd(_) => ModeKind::EvenOdd,
+            GameMode::HighLow(_) => ModeKind::HighLow,
+            GameMode::Exact(_) => ModeKind::Exact,
+            GameMode::GuessOne(_) => ModeKind::GuessOne,
+        }
+    }
+}
+
+/// Ошибка проверки режима игры
+#[derive(Clone, Debug, PartialEq)]
+pub enum ModeError {
+    GuessOutOfRange { guess: u8, sides: u8 }, // Число вне диапазона граней
+}
+
+/// Результат одного раунда
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameResult {
+    pub mode: GameMode,
+    pub roll: u8,
+    pub won: bool,
+}
+
+/// Журнал сессии, достаточный для ее точного воспроизведения
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionLog {
+    pub seed: u64,            // Зерно генератора бросков
+    pub sides: u8,            // Число граней кубика
+    pub modes: Vec<GameMode>, // Выборы игрока по раундам
+}
+
+/// Вид игры без выбора пользователя
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModeKind {
+    EvenOdd,  // Четное/Нечетное
+    HighLow,  // Больше/Меньше
+    Exact,    // Точное число
+    GuessOne, // Угадать единицу
+}
+
+impl ModeKind {
+    /// Все виды игры
+    pub fn all() -> [ModeKind; 4] {
+        [
+            ModeKind::EvenOdd,
+            ModeKind::HighLow,
+            ModeKind::Exact,
+            ModeKind::GuessOne,
+        ]
+    }
+}
+
+/// Состояние пошагового сценария игры в чате
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum GameFsm {
+    /// Выбор режима игры
+    #[default]
+    SelectingMode,
+    /// Выбор варианта внутри режима
+    SelectingChoice(ModeKind),
+    /// Бросок кубика
+    Rolling(GameMode),
+    /// Показ результата
+    Showing { mode: GameMode, roll: u8 },
+}
+
+/// Событие, переводящее сценарий игры в следующее состояние
+#[derive(Clone, Debug, PartialEq)]
+pub enum FsmEvent {
+    ModeSelected(ModeKind), // Пользователь выбрал режим
+    ChoiceMade(GameMode),   // Пользователь сделал выбор
+    Rolled(u8),             // Кубик брошен
+    Restart,                // Новая игра (/play)
+}
+
+/// Ошибка перехода сценария игры
+#[derive(Clone, Debug, PartialEq)]
+pub enum FsmError {
+    InvalidTransition { state: GameFsm, event: FsmEvent }, // Недопустимое событие
+}
+
+impl GameFsm {
+    /// Переход в следующее состояние
+    ///
+    /// `Restart` допустим из любого состояния, остальные события только
+    /// в своем порядке.
+    pub fn transition(self, event: FsmEvent) -> Result<GameFsm, FsmError> {
+        match (self, event) {
+            (_, FsmEvent::Restart) => Ok(GameFsm::SelectingMode),
+            (GameFsm::SelectingMode, FsmEvent::ModeSelected(kind)) => {
+                Ok(GameFsm::SelectingChoice(kind))
+            }
+            (GameFsm::SelectingChoice(kind), FsmEvent::ChoiceMade(mode)) if mode.kind() == kind => {
+                Ok(GameFsm::Rolling(mode))
+            }
+            (GameFsm::Rolling(mode), FsmEvent::Rolled(roll)) => Ok(GameFsm::Showing { mode, roll }),
+            (state, event) => Err(FsmError::InvalidTransition { state, event }),
+        }
+    }
+}
+
+/// Язык пользовательских сообщений
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum Language {
+    #[default]
+    Ru, // Русский
+    En, // English
+}
+
+/// Оформление текстов результатов
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum Theme {
+    #[default]
+    Emoji, // Тексты с эмодзи
+    PlainText, // Простой текст для клиентов с плохой поддержкой эмодзи
+}
+
+/// Игровое событие для подписчиков шины событий
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    RoundPlayed {
+        player: PlayerId,
+        result: GameResult,
+    }, // Раунд сыгран
+    StatsUpdated {
+        player: PlayerId,
+        stats: PlayerStats,
+    }, // Статистика изменилась
+}
+
+/// Ставка на сумму двух кубиков
+#[derive(Clone, Debug, PartialEq)]
+pub enum SumMode {
+    Over(u8),    // Сумма строго больше
+    Under(u8),   // Сумма строго меньше
+    Exactly(u8), // Сумма равна
+}
+
+impl SumMode {
+    /// Проверка суммы двух кубиков
+    pub fn wins(&self, sum: u16) -> bool {
+        match *self {
+            SumMode::Over(target) => sum > target as u16,
+            SumMode::Under(target) => sum < target as u16,
+            SumMode::Exactly(target) => sum == target as u16,
+        }
+    }
+}
+
+/// Уровень волатильности режима
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VolatilityTier {
+    Low,    // Частые небольшие выигрыши (четность, диапазон)
+    Medium, // Промежуточные режимы
+    High,   // Редкие крупные выигрыши (точное число)
+}
+
+/// Уровень игрока для значков
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SkillTier {
+    Novice,  // Новичок или слишком мало игр
+    Skilled, // Опытный
+    Expert,  // Эксперт
+    Lucky,   // Везунчик: процент побед заметно выше ожидаемого
+}
+
+/// Статистика игрока
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PlayerStats {
+    pub wins: u64,
+    pub losses: u64,
+    pub games_played: u64,
+}
+
+impl PlayerStats {
+    /// Восстановление статистики из старого формата `(wins, losses)`
+    ///
+    /// Возвращает `None`, если сумма игр не помещается в `u64`.
+    pub fn from_tuple(wins: u64, losses: u64) -> Option<PlayerStats> {
+        let games_played = wins.checked_add(losses)?;
+        Some(PlayerStats {
+            wins,
+            losses,
+            games_played,
+        })
+    }
+
+    /// Доля выигранных игр (0.0, если игр еще не было)
+    pub fn win_rate(&self) -> f64 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games_played as f64
+    }
+}
+
+/// Итоги игровой сессии
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct SessionSummary {
+    pub games: u64,
+    pub wins: u64,
+    pub best_streak: u32,
+}
+
+impl SessionSummary {
+    /// Многострочный выровненный отчет о сессии для вывода в терминал
+    pub fn render(&self) -> String {
+        let win_rate = if self.games == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.games as f64 * 100.0
+        };
+        let rows = [
+            ("Игр сыграно:", self.games.to_string()),
+            ("Побед:", self.wins.to_string()),
+            ("Процент побед:", format!("{:.1}%", win_rate)),
+            ("Лучшая серия:", self.best_streak.to_string()),
+        ];
+        rows.iter()
+            .map(|(label, value)| format!("{:<15} {:>8}\n", label, value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_choice_all_variants() {
+        assert_eq!(
+            EvenOddChoice::all(),
+            [EvenOddChoice::Even, EvenOddChoice::Odd]
+        );
+        assert_eq!(
+            HighLowChoice::all(),
+            [HighLowChoice::High, HighLowChoice::Low]
+        );
+        assert_eq!(
+            GuessOneChoice::all(),
+            [GuessOneChoice::Yes, GuessOneChoice::No]
+        );
+    }
+
+    #[test]
+    fn test_mode_kind_all_variants() {
+        assert_eq!(
+            ModeKind::all(),
+            [
+                ModeKind::EvenOdd,
+                ModeKind::HighLow,
+                ModeKind::Exact,
+                ModeKind::GuessOne,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_exact_in_range() {
+        assert_eq!(GameMode::Exact(6).validate(6), Ok(()));
+        assert_eq!(GameMode::EvenOdd(EvenOddChoice::Odd).validate(6), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_exact_out_of_range() {
+        assert_eq!(
+            GameMode::Exact(7).validate(6),
+            Err(ModeError::GuessOutOfRange { guess: 7, sides: 6 })
+        );
+        assert_eq!(
+            GameMode::Exact(0).validate(6),
+            Err(ModeError::GuessOutOfRange { guess: 0, sides: 6 })
+        );
+    }
+
+    #[test]
+    fn test_game_fsm_happy_path() {
+        let mode = GameMode::EvenOdd(EvenOddChoice::Even);
+        let state = GameFsm::default()
+            .transition(FsmEvent::ModeSelected(ModeKind::EvenOdd))
+            .and_then(|state| state.transition(FsmEvent::ChoiceMade(mode.clone())))
+            .and_then(|state| state.transition(FsmEvent::Rolled(4)))
+            .unwrap();
+        assert_eq!(state, GameFsm::Showing { mode, roll: 4 });
+        assert_eq!(
+            state.transition(FsmEvent::Restart),
+            Ok(GameFsm::SelectingMode)
+        );
+    }
+
+    #[test]
+    fn test_game_fsm_early_roll() {
+        let state = GameFsm::SelectingChoice(ModeKind::Exact);
+        assert_eq!(
+            state.clone().transition(FsmEvent::Rolled(3)),
+            Err(FsmError::InvalidTransition {
+                state,
+                event: FsmEvent::Rolled(3),
+            })
+        );
+    }
+
+    #[test]
+    fn test_game_fsm_choice_for_other_mode() {
+        let state = GameFsm::SelectingChoice(ModeKind::Exact);
+        assert!(state
+            .transition(FsmEvent::ChoiceMade(GameMode::HighLow(HighLowChoice::High)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_from_tuple_reconstructs_games_played() {
+        let stats = PlayerStats::from_tuple(3, 1).unwrap();
+        assert_eq!(
+            stats,
+            PlayerStats {
+                wins: 3,
+                losses: 1,
+                games_played: 4,
+            }
+        );
+        assert!((stats.win_rate() - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_from_tuple_overflow() {
+        assert_eq!(PlayerStats::from_tuple(u64::MAX, 1), None);
+    }
+
+    #[test]
+    fn test_session_summary_render() {
+        let summary = SessionSummary {
+            games: 3,
+            wins: 2,
+            best_streak: 2,
+        };
+        let rendered = summary.render();
+        assert!(rendered.contains("Игр сыграно:"));
+        assert!(rendered.contains("Побед:"));
+        assert!(rendered.contains("Лучшая серия:"));
+        assert!(rendered.contains("66.7%"));
+        assert_eq!(rendered.lines().count(), 4);
+    }
+
+    #[test]
+    fn test_win_rate_no_games() {
+        assert_eq!(PlayerStats::default().win_rate(), 0.0);
+    }
+}
@@ -0,0 +1,217 @@
+#[cfg(feature = "tokio")]
+use log::error;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+#[cfg(feature = "tokio")]
+use tokio::sync::watch;
+#[cfg(feature = "tokio")]
+use tokio::task::JoinHandle;
+
+use crate::state::{PlayerId, PlayerStats};
+
+/// Хранилище статистики игроков
+pub trait StatsStore {
+    /// Загрузка статистики игрока
+    fn load(&self, player: PlayerId) -> Option<PlayerStats>;
+
+    /// Сохранение статистики игрока
+    fn save(&mut self, player: PlayerId, stats: PlayerStats);
+}
+
+/// Хранилище статистики в памяти процесса
+#[derive(Clone, Debug, Default)]
+pub struct HashMapStore {
+    stats: HashMap<PlayerId, PlayerStats>,
+}
+
+impl StatsStore for HashMapStore {
+    fn load(&self, player: PlayerId) -> Option<PlayerStats> {
+        self.stats.get(&player).cloned()
+    }
+
+    fn save(&mut self, player: PlayerId, stats: PlayerStats) {
+        self.stats.insert(player, stats);
+    }
+}
+
+/// Изменения статистики, еще не записанные в хранилище
+#[derive(Clone, Debug, Default)]
+pub struct DirtyStats {
+    pending: Arc<Mutex<HashMap<PlayerId, PlayerStats>>>,
+}
+
+impl DirtyStats {
+    /// Отметить свежую статистику игрока для записи
+    pub fn mark(&self, player: PlayerId, stats: PlayerStats) {
+        self.pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(player, stats);
+    }
+
+    /// Забрать все накопленные изменения
+    #[cfg(feature = "tokio")]
+    fn take(&self) -> HashMap<PlayerId, PlayerStats> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+/// Запись накопленных изменений в хранилище
+#[cfg(feature = "tokio")]
+fn flush<S: StatsStore>(store: &Mutex<S>, dirty: &DirtyStats) {
+    let pending = dirty.take();
+    if pending.is_empty() {
+        return;
+    }
+    let mut store = store.lock().unwrap_or_else(PoisonError::into_inner);
+    for (player, stats) in pending {
+        store.save(player, stats);
+    }
+}
+
+/// Минимальный период записи статистики
+#[cfg(feature = "tokio")]
+pub const MIN_FLUSH_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Запись накопленных изменений в пуле блокирующих задач
+///
+/// `StatsStore::save` может блокировать (например, запрос к Redis),
+/// поэтому запись не выполняется на рабочем потоке tokio.
+#[cfg(feature = "tokio")]
+async fn flush_blocking<S>(store: &Arc<Mutex<S>>, dirty: &DirtyStats)
+where
+    S: StatsStore + Send + 'static,
+{
+    let store = Arc::clone(store);
+    let dirty = dirty.clone();
+    if let Err(err) = tokio::task::spawn_blocking(move || flush(&store, &dirty)).await {
+        error!("Stats flush failed: {}", err);
+    }
+}
+
+/// Запуск фоновой задачи, периодически сохраняющей статистику
+///
+/// Нулевой `interval` поднимается до `MIN_FLUSH_INTERVAL`. Запись идет
+/// через `spawn_blocking`, так что блокирующее хранилище не задерживает
+/// игру. Задача завершается после финальной записи, когда в `shutdown`
+/// отправлено `true` или отправитель уничтожен.
+///
+/// Доступна с feature `tokio`, включенной по умолчанию.
+#[cfg(feature = "tokio")]
+pub fn spawn_flusher<S>(
+    store: Arc<Mutex<S>>,
+    dirty: DirtyStats,
+    interval: Duration,
+    mut shutdown: watch::Receiver<bool>,
+) -> JoinHandle<()>
+where
+    S: StatsStore + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval.max(MIN_FLUSH_INTERVAL));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => flush_blocking(&store, &dirty).await,
+                changed = shutdown.changed() => {
+                    if changed.is_err() || *shutdown.borrow() {
+                        break;
+                    }
+                }
+            }
+        }
+        flush_blocking(&store, &dirty).await;
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_hash_map_store_round_trip() {
+        let mut store = HashMapStore::default();
+        let stats = PlayerStats::from_tuple(4, 2).unwrap();
+        store.save(7, stats.clone());
+        assert_eq!(store.load(7), Some(stats));
+    }
+
+    #[test]
+    fn test_hash_map_store_missing_player() {
+        let store = HashMapStore::default();
+        assert_eq!(store.load(1), None);
+    }
+
+    #[test]
+    fn test_hash_map_store_overwrites() {
+        let mut store = HashMapStore::default();
+        store.save(7, PlayerStats::from_tuple(1, 0).unwrap());
+        store.save(7, PlayerStats::from_tuple(1, 1).unwrap());
+        assert_eq!(store.load(7).unwrap().games_played, 2);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_spawn_flusher_flushes_on_interval() {
+        let store = Arc::new(Mutex::new(HashMapStore::default()));
+        let dirty = DirtyStats::default();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let handle = spawn_flusher(
+            store.clone(),
+            dirty.clone(),
+            Duration::from_millis(10),
+            shutdown_rx,
+        );
+
+        let stats = PlayerStats::from_tuple(2, 1).unwrap();
+        dirty.mark(3, stats.clone());
+        let flushed = tokio::time::timeout(Duration::from_secs(10), async {
+            while store.lock().unwrap().load(3).is_none() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+        assert!(flushed.is_ok());
+        assert_eq!(store.lock().unwrap().load(3), Some(stats));
+
+        shutdown_tx.send(true).unwrap();
+        handle.await.unwrap();
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_spawn_flusher_zero_interval() {
+        let store = Arc::new(Mutex::new(HashMapStore::default()));
+        let dirty = DirtyStats::default();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let handle = spawn_flusher(store.clone(), dirty.clone(), Duration::ZERO, shutdown_rx);
+
+        let stats = PlayerStats::from_tuple(1, 1).unwrap();
+        dirty.mark(4, stats.clone());
+        shutdown_tx.send(true).unwrap();
+        handle.await.unwrap();
+        assert_eq!(store.lock().unwrap().load(4), Some(stats));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_spawn_flusher_flushes_on_shutdown() {
+        let store = Arc::new(Mutex::new(HashMapStore::default()));
+        let dirty = DirtyStats::default();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let handle = spawn_flusher(
+            store.clone(),
+            dirty.clone(),
+            Duration::from_secs(3600),
+            shutdown_rx,
+        );
+
+        let stats = PlayerStats::from_tuple(0, 5).unwrap();
+        dirty.mark(9, stats.clone());
+        drop(shutdown_tx);
+        handle.await.unwrap();
+        assert_eq!(store.lock().unwrap().load(9), Some(stats));
+    }
+}